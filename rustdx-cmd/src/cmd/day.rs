@@ -111,25 +111,11 @@ impl DayCmd {
 
     /// 匹配 `.day` 之前的内容：比如 `sz000001`
     pub fn stocklist(&self) -> Option<fetch_code::StockList> {
-        use crate::io::read_xlsx;
-        use fetch_code::get_offical_stocks;
-        match (
+        stocklist(
             self.stocklist.as_deref(),
             self.exchange.as_deref(),
             self.xlsx_col,
-        ) {
-            (Some("official"), _, _) => get_offical_stocks("official").ok(),
-            (Some("sse"), _, _) => get_offical_stocks("sse").ok(),
-            (Some("szse"), _, _) => get_offical_stocks("szse").ok(),
-            (Some(ex), Some(prefix), _) if ex.len() == 6 || ex.contains(',') => {
-                self.parse_list(prefix)
-            }
-            (Some(ex), Some("sz"), _) => read_xlsx(ex, 4, "sz"),
-            (Some(ex), Some("sh"), _) => read_xlsx(ex, 0, "sh"),
-            (Some(ex), None, Some(n)) => read_xlsx(ex, n, ""),
-            (Some(ex), Some(prefix), Some(n)) => read_xlsx(ex, n, prefix),
-            _ => self.parse_list(""),
-        }
+        )
     }
 
     /// 筛选 sz/sh 交易所和股票代码的开头，并把代码转换为 u32
@@ -152,15 +138,36 @@ impl DayCmd {
             c.unwrap_or(0),
         )
     }
+}
 
-    fn parse_list(&self, p: &str) -> Option<StockList> {
-        let prefix = |x: &str| format!("{}{}", auto_prefix(p, x), x);
-        self.stocklist
-            .as_ref()
-            .map(|s| s.split(',').map(prefix).collect())
+/// 根据 `-l`、`-e`、`-x` 参数获取股票列表：`rustdx day -h l` 查看详细使用说明。
+pub fn stocklist(
+    list: Option<&str>,
+    exchange: Option<&str>,
+    xlsx_col: Option<usize>,
+) -> Option<StockList> {
+    use crate::io::read_xlsx;
+    use fetch_code::get_offical_stocks;
+    match (list, exchange, xlsx_col) {
+        (Some("official"), _, _) => get_offical_stocks("official").ok(),
+        (Some("sse"), _, _) => get_offical_stocks("sse").ok(),
+        (Some("szse"), _, _) => get_offical_stocks("szse").ok(),
+        (Some(ex), Some(prefix), _) if ex.len() == 6 || ex.contains(',') => {
+            parse_list(list, prefix)
+        }
+        (Some(ex), Some("sz"), _) => read_xlsx(ex, 4, "sz"),
+        (Some(ex), Some("sh"), _) => read_xlsx(ex, 0, "sh"),
+        (Some(ex), None, Some(n)) => read_xlsx(ex, n, ""),
+        (Some(ex), Some(prefix), Some(n)) => read_xlsx(ex, n, prefix),
+        _ => parse_list(list, ""),
     }
 }
 
+fn parse_list(list: Option<&str>, p: &str) -> Option<StockList> {
+    let prefix = |x: &str| format!("{}{}", auto_prefix(p, x), x);
+    list.map(|s| s.split(',').map(prefix).collect())
+}
+
 #[inline]
 pub fn auto_prefix<'a>(prefix: &'a str, code: &'a str) -> &'a str {
     if prefix == "auto" && &code[0..1] == "6" {
@@ -173,7 +180,7 @@ pub fn auto_prefix<'a>(prefix: &'a str, code: &'a str) -> &'a str {
 }
 
 #[rustfmt::skip]
-const DAYCMD_EXCHANGE: &str = "--exchange 或 -e ：
+pub(super) const DAYCMD_EXCHANGE: &str = "--exchange 或 -e ：
 指定 day 文件的代码开头，一般搭配 `-l` 使用：
  * `sz`
  * `sh`
@@ -184,7 +191,7 @@ const DAYCMD_EXCHANGE: &str = "--exchange 或 -e ：
 // 【todo】如果提供 txt 文件路径，则读取里面的六位代码数据。使用 `\\n` 分隔。
 // 【todo】如果提供数据库路径，则使用数据库的股票代码。
 #[rustfmt::skip]
-const DAYCMD_STOCKLIST: &str = "--stocklist 或 -l ：
+pub(super) const DAYCMD_STOCKLIST: &str = "--stocklist 或 -l ：
 匹配 `.day` 之前的内容：比如 `sz000001`。具体用法：
  * `-l official` 从上交所和深交所官网获取最新的 A 股、科创板、创业板股票代码列表
  * `-l sse` 从上交所官网获取 A 股、科创板股票代码列表
@@ -0,0 +1,139 @@
+use super::day::{stocklist, DAYCMD_EXCHANGE, DAYCMD_STOCKLIST};
+use argh::FromArgs;
+use eyre::{anyhow, Result};
+use rustdx::file::lc::LcFile;
+use rustdx_cmd::fetch_code::StockList;
+
+/// 例子：`rustdx lc /vdb/tmp/tdx/sh/fzline/ /vdb/tmp/tdx/sz/fzline/ -l official -m 5 -o clickhouse`。
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "lc")]
+pub struct LcCmd {
+    /// 必选。指定一个或多个含 *.lc1 或 *.lc5（或旧格式 *.1、*.5）文件的文件夹路径（会扫描子文件夹，
+    /// 比如 vipdoc/sh 下的 minline 和 fzline）。使用空格分隔每个路径。
+    #[argh(positional)]
+    pub path: Vec<std::path::PathBuf>,
+
+    /// 可选。解析后的输出方式。`rustdx lc -h o` 查看详细使用说明。
+    /// 默认值为 lc.csv，表示输出 csv 格式，且保存到当前目录的 lc.csv 文件中。
+    #[argh(option, short = 'o', default = "String::from(\"lc.csv\")")]
+    pub output: String,
+
+    /// 可选。指定时，表示保存 csv 文件。只针对非 csv output 有效。
+    #[argh(switch, short = 'k', long = "keep-csv")]
+    pub keep_csv: bool,
+
+    /// 可选。只解析 1 分钟线（*.lc1）或者 5 分钟线（*.lc5）：取值为 1 或 5。
+    /// 默认解析两者。
+    #[argh(option, short = 'm')]
+    pub minutes: Option<u16>,
+
+    /// 可选。指定 6 位代码来解析股票。与 `rustdx day -h l` 的用法相同。
+    #[argh(option, short = 'l')]
+    pub stocklist: Option<String>,
+
+    /// 可选。指定交易所或者代码开头的文件。与 `rustdx day -h e` 的用法相同。
+    #[argh(option, short = 'e')]
+    pub exchange: Option<String>,
+
+    /// 可选。`lc -e sh -l xlsx_path.xlsx -x 0`
+    #[argh(option, short = 'x', long = "xlsx-col")]
+    pub xlsx_col: Option<usize>,
+
+    /// 可选。匹配 6 位代码的前几位。比如：
+    /// `-e sz -c 0000` 等价于 `-l sz0000开头的股票` 即 `sz0000*.lc*`
+    #[argh(option, short = 'c')]
+    pub code: Option<String>,
+
+    /// 可选。指定解析文件的数量。如果指定多个路径，则为每个路径下待解析的文件数量。
+    #[argh(option, short = 'n')]
+    pub amount: Option<usize>,
+
+    /// 可选。显示详细的使用说明。
+    #[argh(option, short = 'h')]
+    description: Vec<String>,
+
+    /// 可选。指定表名称，默认为 `rustdx.lc`。
+    #[argh(option, short = 't', default = "String::from(\"rustdx.lc\")")]
+    pub table: String,
+}
+
+impl LcCmd {
+    pub fn run(&self) -> Result<()> {
+        if let Some(m) = self.minutes.filter(|m| ![1, 5].contains(m)) {
+            return Err(anyhow!("-m 只能为 1 或 5，而不是 {m}"));
+        }
+        match self.output.as_str() {
+            // 表以 (datetime, exchange, code) 为主键，1 分钟线和 5 分钟线写入同一张表会互相覆盖
+            "clickhouse" if self.minutes.is_none() => Err(anyhow!(
+                "-o clickhouse 必须使用 -m 1 或 -m 5 指定周期；`rustdx lc -h o` 查看详细使用说明"
+            )),
+            "clickhouse" => self.run_clickhouse(),
+            x if x.ends_with("csv") => crate::io::run_lc_csv(self),
+            x => Err(anyhow!(
+                "不支持的输出方式：{x}；`rustdx lc -h o` 查看详细使用说明"
+            )),
+        }
+    }
+
+    /// clickhouse-client --query "INSERT INTO table FORMAT CSVWithNames" < clickhouse[.csv]
+    pub fn run_clickhouse(&self) -> Result<()> {
+        crate::io::setup_clickhouse_lc(&self.table)?;
+        crate::io::run_lc_csv(self)?;
+        crate::io::insert_clickhouse_lc(&self.output, &self.table, self.keep_csv)
+    }
+
+    pub fn help_info(&self) -> &Self {
+        for arg in &self.description {
+            match arg.as_str() {
+                "output" | "o" => println!("{LCCMD_OUTPUT}"),
+                "stocklist" | "l" => println!("{DAYCMD_STOCKLIST}"),
+                "exchange" | "e" => println!("{DAYCMD_EXCHANGE}"),
+                _ => println!(
+                    "请查询以下参数：output stocklist exchange 或者它们的简写 o l \
+                               e；\n使用 `-h e -h l` 的形式查询多个参数的使用方法"
+                ),
+            }
+        }
+        self
+    }
+
+    /// 匹配 `.lc1` 或 `.lc5` 之前的内容：比如 `sz000001`
+    pub fn stocklist(&self) -> Option<StockList> {
+        stocklist(
+            self.stocklist.as_deref(),
+            self.exchange.as_deref(),
+            self.xlsx_col,
+        )
+    }
+
+    /// 筛选周期、交易所、代码开头和股票列表。与 [`DayCmd::filter_ec`][super::DayCmd::filter_ec]
+    /// 对 day 文件的筛选方式相同。
+    pub fn filter(&self, f: &LcFile, hm: Option<&StockList>) -> bool {
+        self.minutes.is_none_or(|m| m == f.period.minutes())
+            && f.matches(self.exchange.as_deref(), self.code.as_deref())
+            && hm.is_none_or(|m| m.contains(&f.symbol()))
+    }
+}
+
+#[rustfmt::skip]
+const LCCMD_OUTPUT: &str = "--output 或 -o ：
+解析后的输出方式：
+`-o csv_path.csv` 保存成 csv 格式，默认值为 lc.csv，表示当前目录的 lc.csv 文件
+`-o clickhouse` 保存成 csv 格式，并把 csv 的数据插入到 clickhouse 数据库
+
+注意：
+1. 成功插入到 clickhouse 数据库之后，默认会删除掉解析的 lc.csv 文件。
+   如果需要保存这个文件，使用 `-k` 参数：`-o clickhouse -k`。
+2. clickhouse 数据库必须先建表再插入数据，因此本工具会提前建表，以 (datetime, exchange, code)
+   为主键。1 分钟线和 5 分钟线的时间会重叠，所以 `-o clickhouse` 必须指定 `-m 1` 或 `-m 5`，
+   并使用 `-t` 把它们存到不同的表：
+   `rustdx lc path -m 1 -o clickhouse -t rustdx.lc1`
+   `rustdx lc path -m 5 -o clickhouse -t rustdx.lc5`
+";
+
+#[test]
+fn clickhouse_requires_minutes() {
+    let cmd = LcCmd::from_args(&["lc"], &["path", "-o", "clickhouse"]).unwrap();
+    let err = cmd.run().unwrap_err();
+    assert!(err.to_string().contains("-m 1 或 -m 5"), "{err}");
+}
@@ -3,10 +3,12 @@ use eyre::Result;
 
 mod day;
 mod east;
+mod lc;
 
 pub use self::{
     day::{auto_prefix, DayCmd},
     east::EastCmd,
+    lc::LcCmd,
 };
 
 const VERSION: &str = env!("RUSTDX_VERSION");
@@ -23,6 +25,7 @@ pub struct TopLevel {
 enum SubCommand {
     Day(DayCmd),
     EastMoney(EastCmd),
+    Lc(LcCmd),
     Help(Show),
 }
 
@@ -45,6 +48,7 @@ impl TopLevel {
         match &self.sub {
             Day(cmd) => cmd.help_info().run(),
             EastMoney(cmd) => cmd.run(),
+            Lc(cmd) => cmd.help_info().run(),
             Help(help) => {
                 if help.version {
                     println!("当前版本号：{VERSION}");
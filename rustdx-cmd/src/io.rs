@@ -1,4 +1,4 @@
-use crate::cmd::DayCmd;
+use crate::cmd::{DayCmd, LcCmd};
 use eyre::{anyhow, Result};
 use rustdx::file::{
     day::fq::Day,
@@ -39,7 +39,7 @@ pub fn run_csv(cmd: &DayCmd) -> Result<()> {
             .flatten()
             .try_for_each(|t| wtr.serialize(t))?;
 
-        print(dir, count, take);
+        print(dir, count, take, "day");
     }
     wtr.flush().map_err(|e| e.into())
 }
@@ -75,7 +75,7 @@ pub fn run_csv_fq(cmd: &DayCmd) -> Result<()> {
             .flatten()
             .try_for_each(|t| wtr.serialize(t))?;
 
-        print(dir, count, take);
+        print(dir, count, take, "day");
     }
     wtr.flush().map_err(|e| e.into())
 }
@@ -121,7 +121,38 @@ pub fn run_csv_fq_previous(cmd: &DayCmd) -> Result<()> {
             .flatten()
             .try_for_each(|t| wtr.serialize(t))?;
 
-        print(dir, count, take);
+        print(dir, count, take, "day");
+    }
+    wtr.flush().map_err(|e| e.into())
+}
+
+/// 解析分钟线文件：`*.lc1`、`*.lc5`
+pub fn run_lc_csv(cmd: &LcCmd) -> Result<()> {
+    let hm = cmd.stocklist();
+    let file = File::create(&cmd.output)?;
+    let mut wtr = csv::WriterBuilder::new()
+        .buffer_capacity(BUFFER_SIZE)
+        .from_writer(file);
+    for dir in &cmd.path {
+        let files = rustdx::file::lc::read_dir(dir)?;
+        let n = files.len();
+        info!("dir: {dir:?} lc 文件数量：{n}");
+        let take = cmd.amount.unwrap_or(n);
+
+        let mut count: usize = 0;
+        files
+            .iter()
+            .filter(|f| cmd.filter(f, hm.as_ref()))
+            .take(take)
+            .filter_map(|f| {
+                count += 1;
+                debug!("#{:06}# {:?}", f.code, f.path);
+                f.rows().ok()
+            })
+            .flatten()
+            .try_for_each(|row| -> Result<()> { Ok(wtr.serialize(row?)?) })?;
+
+        print(dir, count, take, "lc");
     }
     wtr.flush().map_err(|e| e.into())
 }
@@ -141,9 +172,9 @@ fn filter(b: bool, src: &Path, hm: Option<&StockList>, dir: &Path) -> Option<boo
     Some(b && hm.map(|m| m.contains(src)).unwrap_or(true))
 }
 
-fn print(dir: &Path, count: usize, take: usize) {
+fn print(dir: &Path, count: usize, take: usize, ext: &str) {
     if count == 0 && take != 0 {
-        error!("{dir:?} 目录下无 `.day` 文件符合要求");
+        error!("{dir:?} 目录下无 `.{ext}` 文件符合要求");
     } else if take == 0 {
         error!("请输入大于 0 的文件数量");
     } else {
@@ -205,10 +236,62 @@ pub fn setup_clickhouse(fq: bool, table: &str) -> Result<()> {
     Ok(())
 }
 
+/// 分钟线表：以 `DateTime` 为主键，见 [`rustdx::file::lc::LcSerde`]
+pub fn setup_clickhouse_lc(table: &str) -> Result<()> {
+    let create_database = format!("CREATE DATABASE IF NOT EXISTS {}", database_table(table).0);
+    let output = Command::new("clickhouse-client")
+        .args(["--query", &create_database])
+        .output()?;
+    check_output(output);
+    #[rustfmt::skip]
+    let create_table = format!("
+        CREATE TABLE IF NOT EXISTS {table}
+        (
+            `datetime` DateTime('Asia/Shanghai') CODEC(DoubleDelta),
+            `code` FixedString(6),
+            `exchange` LowCardinality(String),
+            `open` Float32,
+            `high` Float32,
+            `low` Float32,
+            `close` Float32,
+            `amount` Float64,
+            `vol` Float64
+        )
+        ENGINE = ReplacingMergeTree()
+        ORDER BY (datetime, exchange, code)
+    ");
+    let output = Command::new("clickhouse-client")
+        .args(["--query", &create_table])
+        .output()?;
+    check_output(output);
+    Ok(())
+}
+
 pub fn insert_clickhouse(output: &impl AsRef<Path>, table: &str, keep: bool) -> Result<()> {
+    insert_clickhouse_with(output, table, keep, &[])
+}
+
+/// 插入分钟线：datetime 为 `%Y-%m-%d %H:%M` 格式，没有秒，需要 clickhouse 以 best_effort 方式解析。
+pub fn insert_clickhouse_lc(output: &impl AsRef<Path>, table: &str, keep: bool) -> Result<()> {
+    insert_clickhouse_with(
+        output,
+        table,
+        keep,
+        &["--date_time_input_format", "best_effort"],
+    )
+}
+
+/// settings 为 `--query` 之前的 clickhouse-client 参数。
+fn insert_clickhouse_with(
+    output: &impl AsRef<Path>,
+    table: &str,
+    keep: bool,
+    settings: &[&str],
+) -> Result<()> {
     use subprocess::{Exec, Redirection};
     let query = format!("INSERT INTO {table} FORMAT CSVWithNames");
     let capture = Exec::cmd("clickhouse-client")
+        .args(settings)
         .args(&["--query", &query])
         .stdin(Redirection::File(File::open(output)?))
         .capture()?;
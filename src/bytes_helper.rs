@@ -39,6 +39,16 @@ pub fn u8_from_le_bytes(slice: &[u8], pos: usize) -> u8 {
     })
 }
 
+/// 以 GBK 解码。遇到无效的字节时替换成 `U+FFFD`，并记录一条警告，而不是 panic：
+/// 这些字节通常来自服务器或者文件，无法保证编码正确。
+pub fn gbk_lossy(bytes: &[u8]) -> String {
+    let (s, had_errors) = encoding_rs::GBK.decode_without_bom_handling(bytes);
+    if had_errors {
+        log::warn!("无效的 GBK 字节：{bytes:02x?}");
+    }
+    s.into_owned()
+}
+
 /// 把 6 位 u32 日期转化成 `%Y-%m-%d` 格式，比如 `20210801` => `2021-08-01`
 #[inline]
 pub fn date_string(x: u32) -> String {
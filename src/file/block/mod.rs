@@ -0,0 +1,136 @@
+use crate::{bytes_helper::u16_from_le_bytes, Result};
+use std::{collections::HashMap, path::Path};
+
+/// 板块名称到股票代码的映射。
+pub type BlockCodes = HashMap<String, Vec<String>>;
+/// 股票代码到板块名称的映射。
+pub type CodeBlocks = HashMap<String, Vec<String>>;
+
+/// 通达信安装目录 `T0002/hq_cache` 下的板块文件。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockFile {
+    /// 指数板块：`block_zs.dat`
+    Zs,
+    /// 概念板块：`block_gn.dat`
+    Gn,
+    /// 风格板块：`block_fg.dat`
+    Fg,
+    /// 一般板块：`block.dat`
+    General,
+}
+
+impl BlockFile {
+    pub const ALL: [BlockFile; 4] = [
+        BlockFile::Zs,
+        BlockFile::Gn,
+        BlockFile::Fg,
+        BlockFile::General,
+    ];
+
+    /// 板块文件名，也是 [`BlockInfo`][crate::tcp::stock::BlockInfo] 所需的文件名。
+    pub fn filename(self) -> &'static str {
+        match self {
+            BlockFile::Zs => "block_zs.dat",
+            BlockFile::Gn => "block_gn.dat",
+            BlockFile::Fg => "block_fg.dat",
+            BlockFile::General => "block.dat",
+        }
+    }
+
+    /// 读取 `hq_cache` 目录下的此板块文件。
+    pub fn read(self, hq_cache: impl AsRef<Path>) -> Result<Vec<BlockData>> {
+        BlockData::from_file(hq_cache.as_ref().join(self.filename()))
+    }
+}
+
+/// 板块文件（比如 `block_zs.dat`、`block_gn.dat`、`block_fg.dat`）中的一个板块。
+///
+/// 板块文件可以从通达信安装目录 `T0002/hq_cache` 下找到，也可以通过
+/// [`BlockInfo`][crate::tcp::stock::BlockInfo] 从服务器下载。
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BlockData {
+    /// 板块名称
+    pub block_name: String,
+    /// 板块类型
+    pub block_type: u16,
+    /// 6 位股票代码
+    pub codes: Vec<String>,
+}
+
+impl BlockData {
+    /// 文件头的字节长度，之后 2 字节为板块数量。
+    pub const HEADER_LEN: usize = 384;
+    /// 每个板块占用的字节长度：9 字节名称、2 字节股票数量、2 字节板块类型，
+    /// 以及 400 个 7 字节的股票代码。
+    pub const LEN: usize = 2813;
+
+    /// 解析整个板块文件的字节。参考 pytdx 的 `pytdx.reader.block_reader.BlockReader`。
+    ///
+    /// | 位置 | 0 ~ 383 | 384 ~ 385 | 386 ~               |
+    /// | ---- | ------- | --------- | ------------------- |
+    /// | 含义 | 文件头  | 板块数量  | 每 2813 字节一个板块 |
+    pub fn parse(bytes: &[u8]) -> Vec<BlockData> {
+        if bytes.len() < Self::HEADER_LEN + 2 {
+            return Vec::new();
+        }
+        let count = u16_from_le_bytes(bytes, Self::HEADER_LEN) as usize;
+        bytes[Self::HEADER_LEN + 2..]
+            .chunks_exact(Self::LEN)
+            .take(count)
+            .map(Self::from_chunk)
+            .collect()
+    }
+
+    /// 一次性以**同步**方式读取单个板块文件，然后使用 [`BlockData::parse`] 解析。
+    pub fn from_file(p: impl AsRef<Path>) -> Result<Vec<BlockData>> {
+        Ok(Self::parse(&std::fs::read(p)?))
+    }
+
+    /// 把板块转化成 HashMap ：key 为板块名称，value 为该板块的股票代码。
+    pub fn block_hashmap(blocks: impl IntoIterator<Item = Self>) -> BlockCodes {
+        blocks
+            .into_iter()
+            .map(|b| (b.block_name, b.codes))
+            .collect()
+    }
+
+    /// 把板块转化成 HashMap ：key 为股票代码，value 为该股票所属的所有板块名称。
+    pub fn code_hashmap(blocks: &[Self]) -> CodeBlocks {
+        let mut hm = CodeBlocks::with_capacity(blocks.len() * 8);
+        for block in blocks {
+            for code in &block.codes {
+                hm.entry(code.clone())
+                    .or_default()
+                    .push(block.block_name.clone());
+            }
+        }
+        hm
+    }
+
+    /// 解析一个板块，`chunk` 为长度 2813 的 slice：
+    ///
+    /// | 位置 | 0 ~ 8          | 9 ~ 10   | 11 ~ 12  | 13 ~                        |
+    /// | ---- | -------------- | -------- | -------- | --------------------------- |
+    /// | 类型 | GBK 编码字符串 | u16      | u16      | 每 7 字节一个 UTF-8 字符串  |
+    /// | 含义 | 板块名称       | 股票数量 | 板块类型 | 股票代码（以 `\0` 结尾）    |
+    pub fn from_chunk(chunk: &[u8]) -> Self {
+        let block_name = crate::bytes_helper::gbk_lossy(until_nul(&chunk[..9]));
+        let count = u16_from_le_bytes(chunk, 9) as usize;
+        let block_type = u16_from_le_bytes(chunk, 11);
+        let codes = chunk[13..]
+            .chunks_exact(7)
+            .take(count)
+            .map(|c| String::from_utf8_lossy(until_nul(c)).into_owned())
+            .collect();
+        Self {
+            block_name,
+            block_type,
+            codes,
+        }
+    }
+}
+
+/// 舍弃第一个 `\0` 及之后的字节。
+fn until_nul(bytes: &[u8]) -> &[u8] {
+    &bytes[..bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len())]
+}
@@ -82,6 +82,35 @@ impl Day {
             .collect())
     }
 
+    /// 转化成 `*.day` 文件中的 32 个字节，是 [`Day::from_bytes`] 的逆过程：
+    /// 价格乘以 100 之后四舍五入为 u32；保留字段为 0。
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let price = |p: f32| ((p as f64 * 100.).round() as u32).to_le_bytes();
+        let mut arr = [0; 32];
+        arr[0..4].copy_from_slice(&self.date.to_le_bytes());
+        arr[4..8].copy_from_slice(&price(self.open));
+        arr[8..12].copy_from_slice(&price(self.high));
+        arr[12..16].copy_from_slice(&price(self.low));
+        arr[16..20].copy_from_slice(&price(self.close));
+        arr[20..24].copy_from_slice(&self.amount.to_le_bytes());
+        arr[24..28].copy_from_slice(&self.vol.to_le_bytes());
+        arr
+    }
+
+    /// 以**同步**方式把日线追加到 `*.day` 文件末尾；文件不存在时，会创建该文件。
+    ///
+    /// 注意：不会检查日期是否与文件中已有的日线重复或者连续。
+    pub fn append_to_file<P: AsRef<Path>>(days: &[Day], p: P) -> crate::Result<()> {
+        use std::io::Write;
+        let bytes: Vec<u8> = days.iter().flat_map(Day::to_bytes).collect();
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(p)?
+            .write_all(&bytes)?;
+        Ok(())
+    }
+
     /// `%Y-%m-%d` 格式的日期
     pub fn date_string(&self) -> String {
         crate::bytes_helper::date_string(self.date)
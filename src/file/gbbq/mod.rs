@@ -71,6 +71,24 @@ impl<'a> Gbbq<'a> {
         }
     }
 
+    /// [`Gbbq::from_chunk`] 的逆过程：转化成解密后的 29 个字节，第 7 个字节为 0。
+    /// 使用 [`encrypt`] 加密之后，才是 `gbbq` 文件中的数据。
+    ///
+    /// ## panic
+    /// 当 code 不是 6 个字节时，程序会 panic。
+    pub fn to_chunk(&self) -> [u8; 29] {
+        let mut chunk = [0; 29];
+        chunk[0] = self.market;
+        chunk[1..7].copy_from_slice(self.code.as_bytes());
+        chunk[8..12].copy_from_slice(&self.date.to_le_bytes());
+        chunk[12] = self.category;
+        chunk[13..17].copy_from_slice(&self.fh_qltp.to_le_bytes());
+        chunk[17..21].copy_from_slice(&self.pgj_qzgb.to_le_bytes());
+        chunk[21..25].copy_from_slice(&self.sg_hltp.to_le_bytes());
+        chunk[25..29].copy_from_slice(&self.pg_hzgb.to_le_bytes());
+        chunk
+    }
+
     // 未解密二进制数据转化成 [`Gbbq`]
     pub fn iter(bytes: &mut [u8]) -> impl Iterator<Item = Gbbq<'_>> {
         bytes.chunks_exact_mut(29).map(parse).map(Gbbq::from_chunk)
@@ -128,6 +146,22 @@ impl<'a> Gbbq<'a> {
     }
 }
 
+/// 从服务器获取的除权除息数据，比如用来补充 `gbbq` 文件中没有的记录。
+impl<'a> From<&'a crate::tcp::stock::XdxrData> for Gbbq<'a> {
+    fn from(x: &'a crate::tcp::stock::XdxrData) -> Self {
+        Self {
+            market: x.market,
+            code: &x.code,
+            date: x.date,
+            category: x.category,
+            fh_qltp: x.fh_qltp,
+            pgj_qzgb: x.pgj_qzgb,
+            sg_hltp: x.sg_hltp,
+            pg_hzgb: x.pg_hzgb,
+        }
+    }
+}
+
 pub struct Gbbqs {
     data: Vec<u8>,
     /// 股本变迁的记录条数。这个数据在读取 `gbbq` 文件时就已经被解析了。
@@ -150,6 +184,43 @@ impl Gbbqs {
         })
     }
 
+    /// 由多条记录构造（已解密的）股本变迁数据，比如修正或者合并之后的记录。
+    ///
+    /// 注意：通达信的 `gbbq` 文件中，记录按照股票代码、日期排序，
+    /// [`Gbbq::filter_hashmap`] 也依赖于此顺序，所以请先排序再调用此方法。
+    pub fn from_records<'a>(records: impl IntoIterator<Item = Gbbq<'a>>) -> Self {
+        let mut data = vec![0; 4];
+        let mut count = 0u32;
+        for g in records {
+            data.extend_from_slice(&g.to_chunk());
+            count += 1;
+        }
+        data[..4].copy_from_slice(&count.to_le_bytes());
+        Self {
+            data,
+            count: count as usize,
+            parsed: true,
+        }
+    }
+
+    /// 转化成 `gbbq` 文件的二进制数据：前 4 个字节为记录条数，之后为加密后的记录。
+    ///
+    /// 无论是否已经解密（调用 [`Gbbqs::to_vec`]），结果都是加密的，且不改变自身的数据。
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.data.clone();
+        if self.parsed {
+            bytes[4..].chunks_exact_mut(29).for_each(|c| {
+                encrypt(c);
+            });
+        }
+        bytes
+    }
+
+    /// 以**同步**方式写入 `gbbq` 文件，见 [`Gbbqs::to_bytes`]。
+    pub fn to_file(&self, p: impl AsRef<std::path::Path>) -> Result<()> {
+        Ok(std::fs::write(p, self.to_bytes())?)
+    }
+
     /// 产生 [`Gbbq`] 的 `Vec` 。
     ///
     /// 注意：
@@ -206,34 +277,48 @@ impl Gbbqs {
 ///
 /// TODO: item 改写成 &mut [u8] ，`GbbqRaw` 需要增加 item 字段
 pub fn parse(encrypt: &mut [u8]) -> &[u8] {
-    let mut pos = 0usize;
     for i in (0usize..24).step_by(8) {
-        let mut eax = u32_from_le_bytes(KEY, 0x44);
-        let mut ebx = u32_from_le_bytes(encrypt, pos);
-        let mut num = eax ^ ebx;
-        let mut numold = u32_from_le_bytes(encrypt, pos + 4);
+        let mut num = u32_from_le_bytes(KEY, 0x44) ^ u32_from_le_bytes(encrypt, i);
+        let mut numold = u32_from_le_bytes(encrypt, i + 4);
         for j in (4usize..68).step_by(4).rev() {
-            ebx = (num & 0xff0000) >> 16;
-            eax = u32_from_le_bytes(KEY, ebx as usize * 4 + 0x448);
-            ebx = num >> 24;
-            let mut eax_add = u32_from_le_bytes(KEY, ebx as usize * 4 + 0x48);
-            eax = eax.overflowing_add(eax_add).0;
-            ebx = (num & 0xff00) >> 8;
-            let mut eax_xor = u32_from_le_bytes(KEY, ebx as usize * 4 + 0x848);
-            eax ^= eax_xor;
-            ebx = num & 0xff;
-            eax_add = u32_from_le_bytes(KEY, ebx as usize * 4 + 0xc48);
-            eax = eax.overflowing_add(eax_add).0;
-            eax_xor = u32_from_le_bytes(KEY, j);
-            eax ^= eax_xor;
-            ebx = num;
+            let eax = round(num) ^ u32_from_le_bytes(KEY, j);
+            let ebx = num;
             num = numold ^ eax;
             numold = ebx;
         }
         numold ^= u32_from_le_bytes(KEY, 0);
         encrypt[i..i + 4].swap_with_slice(&mut numold.to_le_bytes());
         encrypt[i + 4..i + 8].swap_with_slice(&mut num.to_le_bytes());
-        pos += 8;
     }
     encrypt
 }
+
+/// [`parse`] 的逆过程：把解密后的 29 个 u8 加密成通达信可以读取的数据。
+///
+/// 与 [`parse`] 相同，只有前 24 个字节会被加密，最后 5 个字节保持不变。
+pub fn encrypt(decrypt: &mut [u8]) -> &[u8] {
+    for i in (0usize..24).step_by(8) {
+        let mut num = u32_from_le_bytes(decrypt, i + 4);
+        let mut numold = u32_from_le_bytes(decrypt, i) ^ u32_from_le_bytes(KEY, 0);
+        for j in (4usize..68).step_by(4) {
+            let eax = round(numold) ^ u32_from_le_bytes(KEY, j);
+            let ebx = numold;
+            numold = num ^ eax;
+            num = ebx;
+        }
+        num ^= u32_from_le_bytes(KEY, 0x44);
+        decrypt[i..i + 4].swap_with_slice(&mut num.to_le_bytes());
+        decrypt[i + 4..i + 8].swap_with_slice(&mut numold.to_le_bytes());
+    }
+    decrypt
+}
+
+/// 加密和解密的每一轮中，根据 num 的四个字节查表得到的数据。
+#[inline]
+fn round(num: u32) -> u32 {
+    let key = |offset: usize, byte: u32| u32_from_le_bytes(KEY, offset + byte as usize * 4);
+    let mut eax = key(0x448, (num & 0xff0000) >> 16);
+    eax = eax.wrapping_add(key(0x48, num >> 24));
+    eax ^= key(0x848, (num & 0xff00) >> 8);
+    eax.wrapping_add(key(0xc48, num & 0xff))
+}
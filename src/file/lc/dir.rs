@@ -0,0 +1,170 @@
+use super::{Format, Lc, LcSerde};
+use crate::Result;
+use std::{
+    fs::File,
+    io::{BufReader, ErrorKind, Read},
+    path::{Path, PathBuf},
+};
+
+/// 分钟线文件的周期，由文件扩展名决定。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Period {
+    /// 1 分钟线：`vipdoc/{sh,sz}/minline/*.lc1`，或者旧格式的 `*.1`
+    Min1,
+    /// 5 分钟线：`vipdoc/{sh,sz}/fzline/*.lc5`，或者旧格式的 `*.5`
+    Min5,
+}
+
+impl Period {
+    /// `lc1`、`lc5`，或者旧格式的 `1`、`5`，不含 `.`；其他扩展名返回 None。
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "lc1" | "1" => Some(Period::Min1),
+            "lc5" | "5" => Some(Period::Min5),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Period::Min1 => "lc1",
+            Period::Min5 => "lc5",
+        }
+    }
+
+    /// 每根 K 线的分钟数。
+    pub fn minutes(self) -> u16 {
+        match self {
+            Period::Min1 => 1,
+            Period::Min5 => 5,
+        }
+    }
+}
+
+/// 一个分钟线文件：交易所和代码从文件名（比如 `sh600000.lc1`）中得到。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LcFile {
+    pub path: PathBuf,
+    /// 交易所：比如 `sh`、`sz`
+    pub exchange: String,
+    pub code: u32,
+    pub period: Period,
+}
+
+impl LcFile {
+    /// 文件名不是 `{2 位交易所}{6 位代码}.{扩展名}` 时返回 None，扩展名见 [`Period::from_extension`]。
+    pub fn from_path(path: impl Into<PathBuf>) -> Option<Self> {
+        let path = path.into();
+        let period = Period::from_extension(path.extension()?.to_str()?)?;
+        let stem = path.file_stem()?.to_str()?;
+        if stem.len() != 8 || !stem.is_char_boundary(2) {
+            return None;
+        }
+        let (exchange, code) = stem.split_at(2);
+        if !exchange.bytes().all(|b| b.is_ascii_alphabetic())
+            || !code.bytes().all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        Some(Self {
+            exchange: exchange.into(),
+            code: code.parse().ok()?,
+            period,
+            path,
+        })
+    }
+
+    /// 6 位股票代码
+    pub fn code_string(&self) -> String {
+        format!("{:06}", self.code)
+    }
+
+    /// 带交易所的股票代码，比如 `sh600000`：与 `rustdx day -l` 所匹配的内容相同。
+    pub fn symbol(&self) -> String {
+        format!("{}{:06}", self.exchange, self.code)
+    }
+
+    /// 筛选交易所和股票代码的开头，与 `rustdx day` 的 `-e`、`-c` 参数含义相同：
+    /// 1. exchange 为 None 或 `auto` 时，不筛选交易所；
+    /// 2. code 为 None 时，不筛选代码，否则匹配 6 位代码的前几位。
+    pub fn matches(&self, exchange: Option<&str>, code: Option<&str>) -> bool {
+        exchange.is_none_or(|ex| ex == "auto" || ex == self.exchange)
+            && code.is_none_or(|c| self.code_string().starts_with(c))
+    }
+
+    fn extension(&self) -> &str {
+        self.path.extension().and_then(|s| s.to_str()).unwrap_or("")
+    }
+
+    /// 一次性以**同步**方式读取整个文件。价格的存储方式由 [`Format::detect`] 推断。
+    pub fn read(&self) -> Result<Vec<Lc>> {
+        let bytes = std::fs::read(&self.path)?;
+        Ok(Lc::parse(
+            self.code,
+            &bytes,
+            Format::detect(self.extension(), &bytes),
+        ))
+    }
+
+    /// 以**同步**方式逐根读取 K 线，并转化成带有交易所的 [`LcSerde`]：不会一次性读取整个文件。
+    ///
+    /// 先读取文件开头的几根 K 线，交给 [`Format::detect`] 推断价格的存储方式，之后每次读取 32
+    /// 字节。读取出错时，迭代器返回该错误之后结束；文件末尾不足 32 字节的部分被忽略。
+    pub fn rows(&self) -> Result<impl Iterator<Item = Result<LcSerde>> + '_> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut head = Vec::with_capacity(Format::SAMPLE * 32);
+        (&mut reader)
+            .take((Format::SAMPLE * 32) as u64)
+            .read_to_end(&mut head)?;
+        let format = Format::detect(self.extension(), &head);
+        let from_bytes = match format {
+            Format::Float => Lc::from_bytes,
+            Format::Integer => Lc::from_int_bytes,
+        };
+
+        let code = self.code;
+        let mut done = false;
+        let rest = std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let mut buf = [0; 32];
+            match reader.read_exact(&mut buf) {
+                Ok(()) => Some(Ok(from_bytes(code, &buf))),
+                Err(e) => {
+                    done = true;
+                    (e.kind() != ErrorKind::UnexpectedEof).then(|| Err(e.into()))
+                }
+            }
+        });
+        Ok(Lc::parse(code, &head, format)
+            .into_iter()
+            .map(Ok)
+            .chain(rest)
+            .map(|lc| lc.map(|lc| lc.into_serde_with_exchange(&self.exchange))))
+    }
+}
+
+/// 扫描目录及其子目录下的所有 `*.lc1`、`*.lc5`（以及旧格式的 `*.1`、`*.5`）文件，按路径排序。
+///
+/// 所以既可以指定 `vipdoc/sh/minline`、`vipdoc/sh/fzline` 这样的目录，
+/// 也可以直接指定 `vipdoc/sh` 或 `vipdoc`。文件名不符合
+/// [`LcFile::from_path`] 要求的文件会被忽略。
+pub fn read_dir(dir: impl AsRef<Path>) -> Result<Vec<LcFile>> {
+    let mut files = Vec::new();
+    walk(dir.as_ref(), &mut files)?;
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+fn walk(dir: &Path, files: &mut Vec<LcFile>) -> Result<()> {
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else if let Some(file) = LcFile::from_path(path) {
+            files.push(file);
+        }
+    }
+    Ok(())
+}
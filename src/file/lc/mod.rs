@@ -1,5 +1,8 @@
 use std::path::Path;
 
+mod dir;
+pub use dir::{read_dir, LcFile, Period};
+
 /// 解析 `*.lc` 文件中的一条日线数据，即其 32 个字节所代表的所有信息。
 ///
 /// 注意：这个类型只对 `*.lc` 文件进行了初步解析，
@@ -57,20 +60,98 @@ impl Lc {
         }
     }
 
+    /// 从旧格式的 `*.1`、`*.5` 文件中获取数据（比如 pytdx 的 `TdxMinBarReader` 所读取的文件）。
+    ///
+    /// 与 [`Lc::from_bytes`] 唯一的不同是：04 ~ 19 字节的四个价格以 u32 存储，
+    /// 需要把所解析的数字/100。
+    pub fn from_int_bytes(code: u32, arr: &[u8]) -> Self {
+        use crate::bytes_helper::u32_from_le_bytes;
+        Self {
+            open: u32_from_le_bytes(arr, 4) as f32 / 100.,
+            high: u32_from_le_bytes(arr, 8) as f32 / 100.,
+            low: u32_from_le_bytes(arr, 12) as f32 / 100.,
+            close: u32_from_le_bytes(arr, 16) as f32 / 100.,
+            ..Self::from_bytes(code, arr)
+        }
+    }
+
+    /// 按照 format 解析整个文件的字节：每 32 个字节为一根分钟 K 线。
+    pub fn parse(code: u32, bytes: &[u8], format: Format) -> Vec<Lc> {
+        let from_bytes = match format {
+            Format::Float => Self::from_bytes,
+            Format::Integer => Self::from_int_bytes,
+        };
+        bytes
+            .chunks_exact(32)
+            .map(|b| from_bytes(code, b))
+            .collect()
+    }
+
     /// 一次性以**同步**方式读取单个 `*.lc` 文件所有数据，然后转化成 Vec。
     pub fn from_file_into_vec<P: AsRef<Path>>(code: u32, p: P) -> crate::Result<Vec<Lc>> {
-        Ok(std::fs::read(p)?
-            .chunks_exact(32)
-            .map(|b| Self::from_bytes(code, b))
-            .collect())
+        Ok(Self::parse(code, &std::fs::read(p)?, Format::Float))
+    }
+
+    /// 转化成 `*.lc1`、`*.lc5` 文件中的 32 个字节，是 [`Lc::from_bytes`] 的逆过程：保留字段为 0。
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut arr = [0; 32];
+        arr[0..2].copy_from_slice(&self.date.to_le_bytes());
+        arr[2..4].copy_from_slice(&self.min.to_le_bytes());
+        arr[4..8].copy_from_slice(&self.open.to_le_bytes());
+        arr[8..12].copy_from_slice(&self.high.to_le_bytes());
+        arr[12..16].copy_from_slice(&self.low.to_le_bytes());
+        arr[16..20].copy_from_slice(&self.close.to_le_bytes());
+        arr[20..24].copy_from_slice(&self.amount.to_le_bytes());
+        arr[24..28].copy_from_slice(&self.vol.to_le_bytes());
+        arr
+    }
+
+    /// 转化成旧格式 `*.1`、`*.5` 文件中的 32 个字节，是 [`Lc::from_int_bytes`] 的逆过程：
+    /// 价格乘以 100 之后四舍五入为 u32。
+    pub fn to_int_bytes(&self) -> [u8; 32] {
+        let price = |p: f32| ((p as f64 * 100.).round() as u32).to_le_bytes();
+        let mut arr = self.to_bytes();
+        arr[4..8].copy_from_slice(&price(self.open));
+        arr[8..12].copy_from_slice(&price(self.high));
+        arr[12..16].copy_from_slice(&price(self.low));
+        arr[16..20].copy_from_slice(&price(self.close));
+        arr
+    }
+
+    /// 以**同步**方式把分钟 K 线按照 format 追加到文件末尾；文件不存在时，会创建该文件。
+    ///
+    /// 注意：不会检查时间是否与文件中已有的 K 线重复或者连续，也不会检查 format
+    /// 是否与文件中已有的数据一致。
+    pub fn append_to_file<P: AsRef<Path>>(lcs: &[Lc], p: P, format: Format) -> crate::Result<()> {
+        use std::io::Write;
+        let to_bytes = match format {
+            Format::Float => Self::to_bytes,
+            Format::Integer => Self::to_int_bytes,
+        };
+        let bytes: Vec<u8> = lcs.iter().flat_map(to_bytes).collect();
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(p)?
+            .write_all(&bytes)?;
+        Ok(())
     }
 
     /// 转化成用于（反）序列化的数据类型：
-    /// 6 位字符串的股票代码；%Y-%m-%d 字符串格式的日期；f64 类型的成交额；u64 类型的 vol 。
+    /// 6 位字符串的股票代码；`%Y-%m-%d %H:%M` 字符串格式的日期时间。
+    ///
+    /// `*.lc` 文件的内容不含交易所，所以 exchange 为空字符串；
+    /// 需要交易所时使用 [`Lc::into_serde_with_exchange`]。
     pub fn into_serde_type(self) -> LcSerde {
+        self.into_serde_with_exchange("")
+    }
+
+    /// 与 [`Lc::into_serde_type`] 相同，只是填上交易所，比如 `sh`：一般来自文件名，见 [`LcFile`]。
+    pub fn into_serde_with_exchange(self, exchange: &str) -> LcSerde {
         LcSerde {
             datetime: self.datetime_string(),
             code: format!("{:06}", self.code),
+            exchange: exchange.into(),
             open: self.open,
             high: self.high,
             low: self.low,
@@ -80,10 +161,10 @@ impl Lc {
         }
     }
 
+    /// `%Y-%m-%d %H:%M` 格式的日期时间
     pub fn datetime_string(&self) -> String {
-        self.hm_arr()
-            .iter()
-            .fold(self.date_string(), |acc, &x| format!("{acc:02}:{x:02}"))
+        let [h, m] = self.hm_arr();
+        format!("{} {h:02}:{m:02}", self.date_string())
     }
 
     /// `%Y-%m-%d` 格式的日期
@@ -116,14 +197,69 @@ impl Lc {
     }
 }
 
+/// 分钟线文件中四个价格的存储方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// f32：`*.lc1`、`*.lc5` 文件，见 [`Lc::from_bytes`]
+    Float,
+    /// u32，所解析的数字/100：旧格式的 `*.1`、`*.5` 文件，见 [`Lc::from_int_bytes`]
+    Integer,
+}
+
+impl Format {
+    /// 检查的 K 线数量。
+    const SAMPLE: usize = 8;
+
+    /// 根据扩展名推断存储方式：`lc1`、`lc5` 为 [`Format::Float`]，其他为 [`Format::Integer`]。
+    ///
+    /// 只有当文件开头几根 K 线的价格明显与扩展名矛盾时，才改用另一种方式，并记录一条警告。
+    /// 以 u32 存储的价格（比如 `1050` 表示 10.5 元）被当作 f32 时是非常小的非正规数
+    /// (subnormal)，而以 f32 存储的价格被当作 u32 时是 10 亿左右的数字，所以两者很容易区分：
+    /// 所检查的 K 线的价格都不符合扩展名的方式、且都符合另一种方式时，才认为矛盾。
+    /// 文件不足一根 K 线，或者价格无法区分（比如全为 0）时，使用扩展名。
+    pub fn detect(ext: &str, bytes: &[u8]) -> Self {
+        let by_ext = if ext.starts_with("lc") {
+            Format::Float
+        } else {
+            Format::Integer
+        };
+        let other = match by_ext {
+            Format::Float => Format::Integer,
+            Format::Integer => Format::Float,
+        };
+        let mut chunks = bytes.chunks_exact(32).take(Self::SAMPLE).peekable();
+        if chunks.peek().is_some() && chunks.all(|b| other.fits(b) && !by_ext.fits(b)) {
+            log::warn!("扩展名为 {ext:?} 的分钟线文件中，价格按 {other:?} 存储，而不是 {by_ext:?}");
+            other
+        } else {
+            by_ext
+        }
+    }
+
+    /// 一根 K 线（32 字节）的四个价格是否都是以这种方式存储的合理价格。
+    fn fits(self, bar: &[u8]) -> bool {
+        use crate::bytes_helper::{f32_from_le_bytes, u32_from_le_bytes};
+        (4..20).step_by(4).all(|pos| match self {
+            Format::Float => {
+                let f = f32_from_le_bytes(bar, pos);
+                f.is_normal() && f > 0. && f < 1e7
+            }
+            // 1e9 分（1e7 元）以上的 u32 价格不合理：f32 价格被当作 u32 时在这个范围
+            Format::Integer => (1..1_000_000_000).contains(&u32_from_le_bytes(bar, pos)),
+        })
+    }
+}
+
 /// 用于序列化：比如写入到 csv
 ///
 /// 此结构体暂时待定，未来可能更改。
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct LcSerde {
-    /// `date` 为 `%Y-%m-%d H:M` 文本格式
+    /// `datetime` 为 `%Y-%m-%d %H:%M` 文本格式
     pub datetime: String,
     pub code: String,
+    /// 交易所，比如 `sh`、`sz`；由 [`Lc::into_serde_type`] 得到时为空字符串
+    pub exchange: String,
     pub open: f32,
     pub high: f32,
     pub low: f32,
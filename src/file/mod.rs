@@ -1,3 +1,4 @@
+pub mod block;
 pub mod day;
 pub mod gbbq;
 pub mod lc;
@@ -16,6 +16,30 @@ pub enum Error {
     Invalid { expected: String, found: String },
     #[error("{0}")]
     Custom(&'static str),
+    /// 响应信息（前 16 字节）不完整。
+    #[error("bad response header: {0:02x?}")]
+    BadHeader(Vec<u8>),
+    /// 响应数据比解析所需的字节短。
+    #[error("short frame (expected at least {expected} bytes, found {found})")]
+    ShortFrame { expected: usize, found: usize },
+    /// 响应数据解压失败。
+    #[error("failed to decompress response: {0}")]
+    Decompress(String),
+    /// 代码的字节长度不正确：股票代码不是 6 字节，或者扩展行情的代码超过 9 字节。
+    #[error("invalid security code {0:?}")]
+    InvalidCode(String),
+    /// 文件名的字节长度超过请求字节中对应字段的长度。
+    #[error("filename {name:?} too long (expected at most {max} bytes)")]
+    InvalidFilename { name: String, max: usize },
+    /// 响应数据中的数量与预期不一致。
+    #[error("count mismatch (expected {expected}, found {found})")]
+    CountMismatch { expected: usize, found: usize },
+    /// 响应的序号和消息类型与请求不一致，通常是超时之后迟到的响应。
+    #[error("response does not match request (expected seq/type {expected:x?}, found {found:x?})")]
+    ResponseMismatch {
+        expected: (u32, u16),
+        found: (u32, u16),
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
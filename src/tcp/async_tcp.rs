@@ -0,0 +1,209 @@
+//! 基于 tokio 的异步 Tcp 连接。需要开启 `async` feature。
+//!
+//! 发送的字节和解析方式与同步版本完全一致：任何 [`Tdx`] 类型都可以通过
+//! [`AsyncTcp::recv_parsed`] 异步地获取数据。与同步版本一样，握手包和是否检查响应信息由
+//! [`Tdx::PROTOCOL`] 决定，扩展行情的连接使用 [`AsyncTcp::new_with_protocol`] 建立。
+//!
+//! ```no_run
+//! use rustdx::tcp::{async_tcp::AsyncTcp, stock::Kline};
+//!
+//! # async fn run() -> rustdx::Result<()> {
+//! let mut tcp = AsyncTcp::new().await?;
+//! let data = tcp.recv_parsed(&mut Kline::default()).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{check_handshake, inflate, Protocol, ResponseHeader, Result, Tdx, RECV_SIZE, TIMEOUT};
+use log::trace;
+use std::{future::Future, net::SocketAddr, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+/// 异步 Tcp 连接的一层封装。与 [`Tcp`][super::Tcp] 对应。
+#[derive(Debug)]
+pub struct AsyncTcp {
+    stream: BufReader<TcpStream>,
+    recv: [u8; RECV_SIZE],
+    timeout: Duration,
+    protocol: Protocol,
+    check_header: bool,
+}
+
+impl AsyncTcp {
+    /// 连接 [`STOCK_IP`][super::ip::STOCK_IP] 的第一个地址。已发送三个测试包。
+    pub async fn new() -> Result<Self> {
+        Self::new_with_ip(&super::ip::STOCK_IP[0]).await
+    }
+
+    /// 已发送三个测试包。超时值为 [`TIMEOUT`]。
+    pub async fn new_with_ip(ip: &SocketAddr) -> Result<Self> {
+        Self::new_with_ip_timeout(ip, TIMEOUT).await
+    }
+
+    /// 已发送三个测试包。连接、每次读取和写入的超时值均为 timeout 。
+    pub async fn new_with_ip_timeout(ip: &SocketAddr, timeout: Duration) -> Result<Self> {
+        Self::new_with_protocol(ip, timeout, Protocol::STOCK).await
+    }
+
+    /// 与 [`Tcp::new_with_protocol`][super::Tcp::new_with_protocol] 相同：按照 protocol
+    /// 发送握手包，之后只能发送 [`Tdx::PROTOCOL`] 与之相同的请求。
+    pub async fn new_with_protocol(
+        ip: &SocketAddr,
+        timeout: Duration,
+        protocol: Protocol,
+    ) -> Result<Self> {
+        let stream = with_timeout(timeout, TcpStream::connect(ip)).await?;
+        let mut tcp = Self {
+            stream: BufReader::new(stream),
+            recv: [0; RECV_SIZE],
+            timeout,
+            protocol,
+            check_header: protocol.check_header,
+        };
+        for (i, (pack, tag)) in protocol.handshake.iter().enumerate() {
+            let header = tcp.recv_frame(pack, tag).await?.0;
+            check_handshake(&mut tcp.check_header, i == 0, &header, pack)?;
+        }
+        Ok(tcp)
+    }
+
+    /// 建立连接时使用的协议。
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    /// 与 [`Tcp::check_header`][super::Tcp::check_header] 相同。
+    pub fn check_header(&self) -> bool {
+        self.check_header
+    }
+
+    /// 与 [`send_recv`][super::send_recv] 相同：发送字节，读取 16 字节的响应信息和之后的有效数据，
+    /// 并按照连接的协议检查响应信息。
+    pub async fn send_recv(&mut self, send: &[u8], tag: &str) -> Result<(Vec<u8>, u16, u16)> {
+        let (header, buf) = self.recv_frame(send, tag).await?;
+        if self.check_header {
+            header.check(send)?;
+        }
+        Ok((buf, header.deflate_size, header.inflate_size))
+    }
+
+    /// 发送字节，读取响应信息和有效数据（未解压），不检查序号和消息类型。
+    async fn recv_frame(&mut self, send: &[u8], tag: &str) -> Result<(ResponseHeader, Vec<u8>)> {
+        with_timeout(self.timeout, self.stream.get_mut().write_all(send)).await?;
+        with_timeout(self.timeout, self.stream.read_exact(&mut self.recv)).await?;
+        trace!("{}\nsend: {:?}\nrecv[16B]: {:?}", tag, send, self.recv);
+
+        let header = ResponseHeader::parse(&self.recv)?;
+        let mut buf = vec![0; header.deflate_size as usize];
+        with_timeout(self.timeout, self.stream.read_exact(&mut buf)).await?;
+        Ok((header, buf))
+    }
+
+    /// 与 [`send_recv_decompress`][super::send_recv_decompress] 相同。
+    pub async fn send_recv_decompress(&mut self, send: &[u8], tag: &str) -> Result<Vec<u8>> {
+        let (buf, deflate_size, inflate_size) = self.send_recv(send, tag).await?;
+        inflate(buf, deflate_size, inflate_size)
+    }
+
+    /// 与 [`Tdx::recv_parsed`] 相同：得到和解析响应的字节，并返回解析的数据。
+    ///
+    /// 连接的协议与 [`Tdx::PROTOCOL`] 不一致时返回 [`Error::Invalid`][crate::Error::Invalid]。
+    pub async fn recv_parsed<'t, T: Tdx>(&mut self, tdx: &'t mut T) -> Result<&'t T::Item> {
+        self.protocol.check::<T>()?;
+        let response = self.send_recv_decompress(tdx.send(), T::TAG).await?;
+        tdx.parse(response)?;
+        Ok(tdx.result())
+    }
+
+    pub fn get_ref_recv(&self) -> &[u8] {
+        &self.recv
+    }
+
+    /// 最近一次接收的响应信息。
+    pub fn header(&self) -> ResponseHeader {
+        ResponseHeader::new(&self.recv)
+    }
+}
+
+/// 超时之后返回 [`TimedOut`][std::io::ErrorKind::TimedOut] 错误。
+async fn with_timeout<T>(
+    timeout: Duration,
+    f: impl Future<Output = std::io::Result<T>>,
+) -> Result<T> {
+    match tokio::time::timeout(timeout, f).await {
+        Ok(res) => Ok(res?),
+        Err(e) => Err(std::io::Error::new(std::io::ErrorKind::TimedOut, e).into()),
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn concurrent() -> Result<()> {
+    use super::{
+        tests::{count_server, COUNT},
+        Heartbeat,
+    };
+
+    let server = count_server(Duration::from_millis(10))?;
+    let addr = server.addr;
+    let mut set = tokio::task::JoinSet::new();
+    for market in 0..100 {
+        set.spawn(async move {
+            let mut tcp = AsyncTcp::new_with_ip(&addr).await?;
+            let count = *tcp.recv_parsed(&mut Heartbeat::new(market % 2)).await?;
+            Result::Ok(count)
+        });
+    }
+    while let Some(count) = set.join_next().await {
+        assert_eq!(count.unwrap()?, COUNT);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn timeout() -> Result<()> {
+    let server = super::tests::count_server(Duration::from_millis(150))?;
+    let err = AsyncTcp::new_with_ip(&server.addr).await.unwrap_err();
+    assert!(
+        matches!(err, crate::Error::Io(ref e) if e.kind() == std::io::ErrorKind::TimedOut),
+        "{err:?}"
+    );
+    AsyncTcp::new_with_ip_timeout(&server.addr, Duration::from_millis(500)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn exhq() -> Result<()> {
+    use super::{
+        exhq::{InstrumentCount, EX_SETUP},
+        mock::Fixture,
+        tests::MockServer,
+        Heartbeat,
+    };
+
+    let mut fixture = Fixture::default();
+    fixture.insert_data(EX_SETUP, &[]);
+    fixture.insert_data(InstrumentCount::SEND, &{
+        let mut v = [0; 23];
+        v[19..].copy_from_slice(&54321u32.to_le_bytes());
+        v
+    });
+    let server = MockServer::start(fixture)?;
+
+    // 标准行情的握手包没有记录，连接会被关闭
+    assert!(AsyncTcp::new_with_ip(&server.addr).await.is_err());
+
+    let mut tcp = AsyncTcp::new_with_protocol(&server.addr, TIMEOUT, Protocol::EXHQ).await?;
+    assert_eq!(
+        *tcp.recv_parsed(&mut InstrumentCount::default()).await?,
+        54321
+    );
+    let err = tcp.recv_parsed(&mut Heartbeat::new(0)).await.unwrap_err();
+    assert!(matches!(err, crate::Error::Invalid { .. }), "{err}");
+    Ok(())
+}
@@ -1,6 +1,7 @@
-use super::{u16_from_le_bytes, Result, Tdx};
+use super::{helper::need, u16_from_le_bytes, Tdx};
+use crate::{Error, Result};
 
-/// 心跳包。用于保持 Tcp 连接。
+/// 心跳包。用于保持 Tcp 连接，见 [`KeepAlive`][super::keepalive::KeepAlive]。
 pub type Heartbeat = SecurityCount;
 
 /// 对应 pytdx 的 hq.security_count / GetSecurityCountCmd
@@ -49,8 +50,10 @@ impl Tdx for SecurityCount {
         &self.send
     }
 
-    fn parse(&mut self, response: Vec<u8>) {
+    fn parse(&mut self, response: Vec<u8>) -> Result<()> {
+        need(&response, 2)?;
         self.count = u16_from_le_bytes(&response, 0);
+        Ok(())
     }
 
     fn result(&self) -> &Self::Item {
@@ -130,14 +133,17 @@ impl Tdx for SecurityList {
     }
 
     /// 前 2 字节表示列表的长度，剩余字节中，每 29 字节使用 [`SecurityListData::parse`] 解析。
-    fn parse(&mut self, v: Vec<u8>) {
+    fn parse(&mut self, v: Vec<u8>) -> Result<()> {
+        need(&v, 2)?;
         self.count = u16_from_le_bytes(&v, 0) as usize;
+        need(&v, 2 + self.count * SecurityListData::LEN)?;
         self.data = v[2..]
-            .chunks_exact(29)
-            .map(SecurityListData::parse)
+            .chunks_exact(SecurityListData::LEN)
+            .take(self.count)
+            .map(|b| SecurityListData::parse(self.market, b))
             .collect();
-        debug_assert_eq!(self.count, self.data.len());
         self.response = v;
+        Ok(())
     }
 
     fn result(&self) -> &Self::Item {
@@ -145,25 +151,121 @@ impl Tdx for SecurityList {
     }
 }
 
+impl SecurityList {
+    /// 每次请求最多返回的证券数量。
+    pub const PAGE_SIZE: u16 = 1000;
+
+    /// 在同一个连接上获取某个市场的所有证券：先使用 [`SecurityCount`] 查询证券数量，
+    /// 再以 [`SecurityList::PAGE_SIZE`] 为步长依次请求 [`SecurityList`]。
+    ///
+    /// 当获取的证券总数与 [`SecurityCount`] 的结果不一致时，返回 [`Error::CountMismatch`]。
+    pub fn recv_market(tcp: &mut super::Tcp, market: u16) -> Result<Vec<SecurityListData>> {
+        let total = *SecurityCount::new(market).recv_parsed(tcp)?;
+        recv_pages(total, |start| {
+            let mut list = Self::new(market, start);
+            list.recv_parsed(tcp)?;
+            Ok(list.data.into_vec())
+        })
+    }
+
+    /// 在同一个连接上获取深市和沪市的所有证券（包括指数、股票、基金和债券等），
+    /// 深市在前，沪市在后。见 [`SecurityList::recv_market`]。
+    pub fn recv_all(tcp: &mut super::Tcp) -> Result<Vec<SecurityListData>> {
+        let mut all = Self::recv_market(tcp, 0)?;
+        all.append(&mut Self::recv_market(tcp, 1)?);
+        Ok(all)
+    }
+}
+
+/// 翻页获取证券列表，直到数量达到 total 或者某页为空，然后检查总数。
+fn recv_pages(
+    total: u16,
+    mut page: impl FnMut(u16) -> Result<Vec<SecurityListData>>,
+) -> Result<Vec<SecurityListData>> {
+    let mut all = Vec::with_capacity(total as usize);
+    while all.len() < total as usize {
+        let data = page(all.len() as u16)?;
+        if data.is_empty() {
+            break;
+        }
+        all.extend(data);
+    }
+    if all.len() != total as usize {
+        return Err(Error::CountMismatch {
+            expected: total as usize,
+            found: all.len(),
+        });
+    }
+    Ok(all)
+}
+
+#[test]
+fn recv_pages_boundary() {
+    let data = |n| SecurityListData {
+        market: 0,
+        code: format!("{:06}", n),
+        name: String::new(),
+        volunit: 100,
+        decimal_point: 2,
+        preclose: 0.,
+    };
+    let fake = |total: u16| {
+        move |start: u16| {
+            let end = total.min(start.saturating_add(SecurityList::PAGE_SIZE));
+            Ok((start..end).map(data).collect())
+        }
+    };
+    for total in [0, 1, 999, 1000, 1001, 2000, 2500] {
+        let all = recv_pages(total, fake(total)).unwrap();
+        assert_eq!(all.len(), total as usize);
+        assert!(all
+            .iter()
+            .enumerate()
+            .all(|(i, d)| d.code == format!("{:06}", i)));
+    }
+    // 服务器实际返回的数量比 SecurityCount 少
+    let err = recv_pages(1500, fake(1200)).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::CountMismatch {
+            expected: 1500,
+            found: 1200
+        }
+    ));
+}
+
 #[test]
 fn connection() -> Result<()> {
-    SecurityList::default().recv_parsed(&mut crate::tcp::Tcp::new()?)?;
-    Ok(())
+    crate::tcp::tests::connection(SecurityList::default())
 }
 
-/// [`SecurityList`] 的解析结果。具体为指数、股票、债券等证券的代码、名称。
+/// [`SecurityList`] 的解析结果。具体为指数、股票、债券等证券的代码、名称等信息。
 ///
-/// ## 注意
-/// 有些响应的字节没有被解析，具体查看 [`SecurityListData::parse`] 的说明。
+/// 使用 [`SecurityListData::security_type`] 可以区分证券的类别。
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct SecurityListData {
+    /// 0 代表深市；1 代表沪市。来自请求的市场，而不是响应字节。
+    pub market: u16,
     pub code: String,
     /// `\u0000` 字符表示空格
     pub name: String,
+    /// 每手的数量，一般为 100
+    pub volunit: u16,
+    /// 价格的小数位数：股票、指数为 2；ETF、债券等为 3
+    ///
+    /// K 线的价格以 0.001 为单位，不受此字段影响；实时行情、分时、逐笔成交的价格以 0.01 为单位，
+    /// 对于 3 位小数的证券需要使用 [`SecurityListData::rescale`] 或者各自的 `rescale` 方法换算。
+    pub decimal_point: u8,
+    /// 昨收。注意：少许结果与实际数据有出入
+    pub preclose: f64,
 }
 
 impl SecurityListData {
-    /// 解析 [`SecurityList`] 的响应字节。传入长度为 29 字节序列。
+    /// 每个证券占用的字节长度。
+    pub const LEN: usize = 29;
+
+    /// 解析 [`SecurityList`] 的响应字节。传入长度为 29 字节序列，
+    /// [`SecurityList::parse`][Tdx::parse] 已经检查过长度。
     /// ```python
     /// (
     ///     code,            # UTF-8 编码
@@ -171,23 +273,123 @@ impl SecurityListData {
     ///     name_bytes,      # GBK 编码
     ///     reversed_bytes1,
     ///     decimal_point,   # 2
-    ///     pre_close_raw,   # 少许结果与实际数据有出入，故不解析
+    ///     pre_close_raw,   # 使用 vol_amount 解析
     ///     reversed_bytes2,
     /// ) = struct.unpack("<6sH8s4sBI4s", bytes) # python 表示方式
     /// ```
-    pub fn parse(bytes: &[u8]) -> Self {
-        let code = unsafe { std::str::from_utf8_unchecked(&bytes[0..6]) }.into();
-        let (name, encoding_used, had_errors) = encoding_rs::GBK.decode(&bytes[8..16]);
-        debug_assert_eq!(encoding_used, encoding_rs::GBK);
-        debug_assert!(!had_errors);
-        // let preclose = crate::tcp::helper::vol_amount(u32_from_le_bytes(b, 21) as i32);
+    pub fn parse(market: u16, bytes: &[u8]) -> Self {
+        use crate::bytes_helper::{u32_from_le_bytes, u8_from_le_bytes};
+        let code = String::from_utf8_lossy(&bytes[0..6]).into_owned();
         Self {
+            market,
             code,
-            name: name.into(),
+            name: crate::bytes_helper::gbk_lossy(&bytes[8..16]),
+            volunit: u16_from_le_bytes(bytes, 6),
+            decimal_point: u8_from_le_bytes(bytes, 20),
+            preclose: crate::tcp::helper::vol_amount(u32_from_le_bytes(bytes, 21) as i32),
+        }
+    }
+
+    /// 按照此证券的 decimal_point 换算实时行情、分时、逐笔成交中的价格，
+    /// 见 [`rescale`][crate::tcp::helper::rescale]。
+    pub fn rescale(&self, price: f64) -> f64 {
+        crate::tcp::helper::rescale(price, self.decimal_point)
+    }
+
+    /// 根据市场和代码前缀判断证券类别，见 [`SecurityType::classify`]。
+    pub fn security_type(&self) -> SecurityType {
+        SecurityType::classify(self.market, &self.code)
+    }
+}
+
+/// 证券类别。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+pub enum SecurityType {
+    /// A 股股票
+    Stock,
+    /// 指数（包括通达信自编的板块指数）
+    Index,
+    /// 场内基金 ETF
+    Etf,
+    /// 债券（包括可转债和回购）
+    Bond,
+    /// B 股股票
+    BShare,
+    /// 其他：比如 LOF、封闭式基金、权证等
+    Other,
+}
+
+impl SecurityType {
+    /// 根据市场和代码前缀判断证券类别：
+    ///
+    /// | 类别   | 深市 (market = 0)                  | 沪市 (market = 1)                         |
+    /// | ------ | ---------------------------------- | ----------------------------------------- |
+    /// | Stock  | 000、001、002、003、300、301       | 600、601、603、605、688、689              |
+    /// | Index  | 399                                | 000、880、999                             |
+    /// | Etf    | 159                                | 51、56、58                                |
+    /// | Bond   | 10、11、12、13                     | 01、02、10、11、12、13、14、20            |
+    /// | BShare | 200                                | 900                                       |
+    pub fn classify(market: u16, code: &str) -> Self {
+        let prefix = |p: &[&str]| p.iter().any(|p| code.starts_with(p));
+        match market {
+            0 if prefix(&["000", "001", "002", "003", "300", "301"]) => Self::Stock,
+            0 if prefix(&["399"]) => Self::Index,
+            0 if prefix(&["159"]) => Self::Etf,
+            0 if prefix(&["10", "11", "12", "13"]) => Self::Bond,
+            0 if prefix(&["200"]) => Self::BShare,
+            1 if prefix(&["600", "601", "603", "605", "688", "689"]) => Self::Stock,
+            1 if prefix(&["000", "880", "999"]) => Self::Index,
+            1 if prefix(&["51", "56", "58"]) => Self::Etf,
+            1 if prefix(&["01", "02", "10", "11", "12", "13", "14", "20"]) => Self::Bond,
+            1 if prefix(&["900"]) => Self::BShare,
+            _ => Self::Other,
         }
     }
 }
 
+#[test]
+fn security_list_data_parse() {
+    // 510300 沪深300ETF，每手 100 份，小数位数为 3
+    let mut bytes = [0; 29];
+    bytes[..6].copy_from_slice(b"510300");
+    bytes[6..8].copy_from_slice(&100u16.to_le_bytes());
+    let name = encoding_rs::GBK.encode("300ETF").0;
+    bytes[8..8 + name.len()].copy_from_slice(&name);
+    bytes[20] = 3;
+    bytes[21..25].copy_from_slice(&0x409be76bu32.to_le_bytes());
+
+    let data = SecurityListData::parse(1, &bytes);
+    assert_eq!(data.code, "510300");
+    assert_eq!(data.name, "300ETF\u{0}\u{0}");
+    assert_eq!(data.volunit, 100);
+    assert_eq!(data.decimal_point, 3);
+    assert!((data.preclose - 4.872).abs() < 1e-3);
+    assert_eq!(data.security_type(), SecurityType::Etf);
+    // 实时行情中 4.872 元以 0.01 为单位解析为 48.72
+    assert_eq!(data.rescale(48.72), 4.872);
+
+    // 无效的 GBK 字节被替换成 U+FFFD，而不是 panic
+    bytes[8..16].copy_from_slice(&[0xff, b'E', b'T', b'F', 0x81, b' ', 0, 0]);
+    let data = SecurityListData::parse(1, &bytes);
+    assert_eq!(data.name, "\u{FFFD}ETF\u{FFFD} \u{0}\u{0}");
+}
+
+#[test]
+fn security_type_classify() {
+    use SecurityType::*;
+    #[rustfmt::skip]
+    let cases = [
+        (0, "000001", Stock), (0, "300750", Stock), (0, "399001", Index), (0, "159915", Etf),
+        (0, "123001", Bond),  (0, "200012", BShare), (0, "160105", Other),
+        (1, "600000", Stock), (1, "688981", Stock), (1, "000001", Index), (1, "880001", Index),
+        (1, "510300", Etf),   (1, "113001", Bond),  (1, "204001", Bond),  (1, "900901", BShare),
+        (1, "501000", Other),
+    ];
+    for (market, code, ty) in cases {
+        assert_eq!(SecurityType::classify(market, code), ty, "{market} {code}");
+    }
+}
+
 pub const PACK1: &[u8] = &[
     0x0c, 0x02, 0x18, 0x93, 0x00, 0x01, 0x03, 0x00, 0x03, 0x00, 0x0d, 0x00, 0x01,
 ];
@@ -0,0 +1,158 @@
+//! 在同一个连接上流水线式地发送多个请求。
+//!
+//! [`Tdx::recv_parsed`] 每次请求都要等待响应之后才能发送下一个请求，
+//! 请求 5000 只股票的日线就需要 5000 次往返。[`recv_batch`] 连续写入多个请求，
+//! 再依次读取响应，并根据响应信息把响应交给对应的请求解析。
+//!
+//! ```no_run
+//! use rustdx::tcp::{batch::recv_batch, stock::Kline, Tcp, Tdx};
+//!
+//! let codes = [(0, "000001"), (0, "000002"), (1, "600000")];
+//! let mut klines: Vec<_> = codes
+//!     .iter()
+//!     .map(|&(market, code)| Kline::new(market, code, 9, 0, 800))
+//!     .collect::<rustdx::Result<_>>()?;
+//! recv_batch(&mut Tcp::new()?, &mut klines, 16)?;
+//! for kline in &klines {
+//!     println!("{}: {}", kline.code, kline.result().len());
+//! }
+//! # Ok::<(), rustdx::Error>(())
+//! ```
+
+use super::{inflate, ResponseHeader, Tcp, Tdx};
+use crate::{Error, Result};
+use log::trace;
+use std::{
+    collections::VecDeque,
+    io::{Read, Write},
+};
+
+/// 默认的最大在途请求数量：已发送但还没有收到响应的请求数量。
+pub const DEPTH: usize = 16;
+
+/// 连续发送 tdxs 的请求，并解析各自的响应。解析的数据通过每个 tdx 的
+/// [`Tdx::result`] 获取。
+///
+/// - 在途请求（已发送但还没有收到响应）最多 depth 个（最小为 1 ，此时与逐个请求相同）；
+/// - 每个响应交给在途请求中，序号和消息类型与响应信息一致的最早的请求
+///   （见 [`ResponseHeader::check`]）；没有一致的请求时返回 [`Error::ResponseMismatch`]。
+///
+/// 出错时直接返回错误，此时连接中可能残留未读取的响应，应丢弃此连接。
+///
+/// 连接的协议与 `T::PROTOCOL` 不一致，或者连接不检查响应信息（见
+/// [`Tcp::check_header`]）时，返回 [`Error::Invalid`]：此时无法把响应交给对应的请求。
+pub fn recv_batch<T: Tdx>(tcp: &mut Tcp, tdxs: &mut [T], depth: usize) -> Result<()> {
+    tcp.protocol.check::<T>()?;
+    if !tcp.check_header {
+        return Err(Error::Invalid {
+            expected: "检查响应信息的连接".into(),
+            found: T::PROTOCOL.name.into(),
+        });
+    }
+    let depth = depth.max(1);
+    let mut in_flight = VecDeque::with_capacity(depth.min(tdxs.len()));
+    let mut sent = 0;
+    while sent < tdxs.len() || !in_flight.is_empty() {
+        while sent < tdxs.len() && in_flight.len() < depth {
+            let send = tdxs[sent].send();
+            tcp.stream.write_all(send)?;
+            trace!("{} #{}\nsend: {:?}", T::TAG, sent, send);
+            in_flight.push_back(sent);
+            sent += 1;
+        }
+
+        let (header, buf) = recv_frame(tcp)?;
+        let pos = in_flight
+            .iter()
+            .position(|&i| header.check(tdxs[i].send()).is_ok())
+            .ok_or_else(|| Error::ResponseMismatch {
+                expected: ResponseHeader::request_id(tdxs[in_flight[0]].send()).unwrap_or_default(),
+                found: (header.seq, header.msg_type),
+            })?;
+        let i = in_flight.remove(pos).unwrap();
+        let response = inflate(buf, header.deflate_size, header.inflate_size)?;
+        tdxs[i].parse(response)?;
+    }
+    Ok(())
+}
+
+/// 读取一个响应：16 字节的响应信息和之后未解压的数据。
+fn recv_frame(tcp: &mut Tcp) -> Result<(ResponseHeader, Vec<u8>)> {
+    tcp.buffer.read_exact(&mut tcp.recv)?;
+    let header = ResponseHeader::parse(&tcp.recv)?;
+    let mut buf = vec![0; header.deflate_size as usize];
+    tcp.buffer.read_exact(&mut buf)?;
+    trace!("recv[16B]: {:?}", header);
+    Ok((header, buf))
+}
+
+#[test]
+fn pipelined() -> Result<()> {
+    use super::{
+        mock::{frame, read_request},
+        Heartbeat, PACK1, PACK2, PACK3,
+    };
+    use std::net::TcpListener;
+
+    // 每次先读取 DEPTH 个请求，再按顺序响应：第 i 个请求的响应为 i 。
+    // 如果客户端不是流水线式地发送，服务器会一直等待，客户端读取超时。
+    const N: usize = 50;
+    const DEPTH: usize = 8;
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let server = std::thread::spawn(move || -> std::io::Result<()> {
+        let (mut stream, _) = listener.accept()?;
+        for pack in [PACK1, PACK2, PACK3] {
+            assert_eq!(read_request(&mut stream)?, pack);
+            stream.write_all(&frame(pack, &[]))?;
+        }
+        let mut i = 0;
+        while i < N {
+            let requests: Vec<_> = (i..N.min(i + DEPTH))
+                .map(|_| read_request(&mut stream))
+                .collect::<std::io::Result<_>>()?;
+            // 在途请求不超过 DEPTH 个
+            stream.set_nonblocking(true)?;
+            let pending = stream.peek(&mut [0]);
+            assert_eq!(pending.unwrap_err().kind(), std::io::ErrorKind::WouldBlock);
+            stream.set_nonblocking(false)?;
+            for request in requests {
+                stream.write_all(&frame(&request, &(i as u16).to_le_bytes()))?;
+                i += 1;
+            }
+        }
+        Ok(())
+    });
+
+    let mut tcp = Tcp::new_with_ip(&addr)?;
+    let mut heartbeats: Vec<_> = (0..N).map(|i| Heartbeat::new(i as u16 % 2)).collect();
+    recv_batch(&mut tcp, &mut heartbeats, DEPTH)?;
+    server.join().unwrap()?;
+    for (i, heartbeat) in heartbeats.iter().enumerate() {
+        assert_eq!(*heartbeat.result() as usize, i);
+    }
+    Ok(())
+}
+
+#[test]
+fn mismatched() -> Result<()> {
+    use super::{
+        mock::{frame, Fixture, MockServer},
+        stock::Kline,
+        Heartbeat,
+    };
+
+    let mut fixture = Fixture::handshake();
+    fixture.insert_data(Heartbeat::SEND, &[0x10, 0x27]);
+    fixture.insert(Kline::SEND, frame(Heartbeat::SEND, &[0x10, 0x27]));
+    let server = MockServer::start(fixture)?;
+    let mut tcp = Tcp::new_with_ip(&server.addr)?;
+
+    let mut heartbeats = [Heartbeat::new(0), Heartbeat::new(0)];
+    recv_batch(&mut tcp, &mut heartbeats, DEPTH)?;
+    assert_eq!(heartbeats.map(|h| *h.result()), [10000; 2]);
+
+    let err = recv_batch(&mut tcp, &mut [Kline::default()], DEPTH).unwrap_err();
+    assert!(matches!(err, Error::ResponseMismatch { .. }), "{err:?}");
+    Ok(())
+}
@@ -0,0 +1,227 @@
+//! 带有自动重连和重试的 [`Tcp`] 封装。
+//!
+//! ```no_run
+//! use rustdx::tcp::{client::Client, stock::Kline};
+//! use std::time::Duration;
+//!
+//! let mut client = Client::default();
+//! client.timeout(Duration::from_millis(500)).max_attempts(5);
+//! let data = client.recv_parsed(&mut Kline::default())?;
+//! # Ok::<(), rustdx::Error>(())
+//! ```
+
+use super::{Result, Tcp, Tdx, TIMEOUT};
+use crate::Error;
+use std::{io::ErrorKind, net::SocketAddr, time::Duration};
+
+/// 自动重连和重试的客户端，适用于任何 [`Tdx`] 类型。
+///
+/// 请求失败（比如超时得到 [`WouldBlock`][ErrorKind::WouldBlock]、连接被断开、响应头损坏或错位）时：
+/// 1. 丢弃当前连接，等待 backoff 时间（每次失败之后翻倍）；
+/// 2. 切换到下一个地址重新连接（会重新发送三个测试包）；
+/// 3. 再次发送请求，直到成功或者达到最大尝试次数。
+///
+/// 连接在第一次请求时才建立。
+#[derive(Debug)]
+pub struct Client {
+    addrs: Vec<SocketAddr>,
+    timeout: Duration,
+    max_attempts: usize,
+    backoff: Duration,
+    /// 当前使用的地址在 addrs 中的位置。
+    current: usize,
+    tcp: Option<Tcp>,
+}
+
+/// 默认使用 [`STOCK_IP`][super::ip::STOCK_IP] 中的地址；超时为 [`TIMEOUT`]；
+/// 最多尝试 3 次；初始 backoff 为 100 毫秒。
+impl Default for Client {
+    fn default() -> Self {
+        Self::new(super::ip::STOCK_IP.to_vec()).expect("STOCK_IP 不为空")
+    }
+}
+
+impl Client {
+    /// 依次使用 addrs 中的地址。
+    ///
+    /// 当 addrs 为空时，返回 [`Error::Custom`]。
+    pub fn new(addrs: Vec<SocketAddr>) -> Result<Self> {
+        if addrs.is_empty() {
+            return Err(Error::Custom("Client 的地址不能为空"));
+        }
+        Ok(Self {
+            addrs,
+            timeout: TIMEOUT,
+            max_attempts: 3,
+            backoff: Duration::from_millis(100),
+            current: 0,
+            tcp: None,
+        })
+    }
+
+    /// 修改连接、读取和写入的超时值。对之后新建立的连接生效。
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 修改每个请求的最大尝试次数（包括第一次），最小为 1。
+    pub fn max_attempts(&mut self, max_attempts: usize) -> &mut Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// 修改第一次失败之后的等待时间，之后每次失败翻倍。
+    pub fn backoff(&mut self, backoff: Duration) -> &mut Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// 当前使用的地址。
+    pub fn addr(&self) -> SocketAddr {
+        self.addrs[self.current]
+    }
+
+    /// 得到当前的连接，如果没有连接，则建立连接。此方法不会重试。
+    pub fn tcp(&mut self) -> Result<&mut Tcp> {
+        match self.tcp {
+            Some(ref mut tcp) => Ok(tcp),
+            None => {
+                let tcp = Tcp::new_with_ip_timeout(&self.addrs[self.current], self.timeout)?;
+                Ok(self.tcp.insert(tcp))
+            }
+        }
+    }
+
+    /// 与 [`Tdx::recv_parsed`] 相同，只是失败时会按照上述方式重试。
+    ///
+    /// 遇到无法通过重试解决的错误时，直接返回该错误；否则返回最后一次尝试的错误。
+    pub fn recv_parsed<'t, T: Tdx>(&mut self, tdx: &'t mut T) -> Result<&'t T::Item> {
+        let mut backoff = self.backoff;
+        let mut attempt = 1;
+        loop {
+            match self
+                .tcp()
+                .and_then(|tcp| tdx.recv(tcp))
+                .and_then(|response| tdx.parse(response))
+            {
+                Ok(()) => return Ok(tdx.result()),
+                Err(e) if attempt < self.max_attempts && retryable(&e) => {
+                    log::warn!(
+                        "{} 第 {} 次请求 {} 失败：{}",
+                        T::TAG,
+                        attempt,
+                        self.addr(),
+                        e
+                    );
+                    self.tcp = None;
+                    self.current = (self.current + 1) % self.addrs.len();
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    self.tcp = None;
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+/// 超时、连接断开、响应头损坏或错位等错误可以通过重连解决。
+///
+/// 有效数据不完整（[`Error::ShortFrame`]）或解压失败（[`Error::Decompress`]）时，
+/// 说明服务器对这个请求的响应本身有问题，重试只会得到同样的结果，因此直接返回。
+fn retryable(e: &Error) -> bool {
+    use ErrorKind::*;
+    match e {
+        Error::Io(e) => matches!(
+            e.kind(),
+            WouldBlock
+                | TimedOut
+                | Interrupted
+                | UnexpectedEof
+                | ConnectionRefused
+                | ConnectionReset
+                | ConnectionAborted
+                | NotConnected
+                | BrokenPipe
+        ),
+        Error::BadHeader(_) | Error::ResponseMismatch { .. } => true,
+        _ => false,
+    }
+}
+
+#[test]
+fn failover() -> Result<()> {
+    use super::{
+        tests::{count_server, MockServer, COUNT},
+        Heartbeat,
+    };
+
+    assert!(matches!(Client::new(Vec::new()), Err(Error::Custom(_))));
+
+    let server = count_server(Duration::ZERO)?;
+    let dead = MockServer::dead_addr()?;
+    let mut client = Client::new(vec![dead, server.addr])?;
+    client.backoff(Duration::from_millis(1));
+    assert_eq!(*client.recv_parsed(&mut Heartbeat::new(0))?, COUNT);
+    assert_eq!(client.addr(), server.addr);
+
+    // 服务器断开连接之后，重新连接到另一个服务器
+    let server2 = count_server(Duration::ZERO)?;
+    let mut client = Client::new(vec![server.addr, server2.addr])?;
+    client.backoff(Duration::from_millis(1));
+    client.recv_parsed(&mut Heartbeat::new(0))?;
+    drop(server);
+    client.recv_parsed(&mut Heartbeat::new(0))?;
+    assert_eq!(client.addr(), server2.addr);
+
+    // 达到最大尝试次数
+    drop(server2);
+    let err = client
+        .max_attempts(2)
+        .recv_parsed(&mut Heartbeat::new(0))
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::Io(ref e) if e.kind() == ErrorKind::ConnectionRefused),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn timeout() -> Result<()> {
+    use super::{
+        tests::{count_server, COUNT},
+        Heartbeat,
+    };
+
+    let server = count_server(Duration::from_millis(150))?;
+    let mut client = Client::new(vec![server.addr])?;
+    client.max_attempts(1);
+    let err = client.recv_parsed(&mut Heartbeat::new(0)).unwrap_err();
+    assert!(retryable(&err), "{err:?}");
+
+    client.timeout(Duration::from_millis(500));
+    assert_eq!(*client.recv_parsed(&mut Heartbeat::new(0))?, COUNT);
+    Ok(())
+}
+
+#[test]
+fn short_frame_not_retried() -> Result<()> {
+    use super::{mock::Fixture, tests::MockServer, Heartbeat};
+
+    // 心跳包的有效数据为空：解析时得到 ShortFrame
+    let mut fixture = Fixture::handshake();
+    fixture.fallback_data(&[]);
+    let server = MockServer::start(fixture)?;
+    let mut client = Client::new(vec![server.addr])?;
+    client.max_attempts(3).backoff(Duration::from_millis(1));
+    let err = client.recv_parsed(&mut Heartbeat::new(0)).unwrap_err();
+    assert!(matches!(err, Error::ShortFrame { .. }), "{err:?}");
+    // 三个测试包和一次请求
+    assert_eq!(server.requests(), 4);
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use super::code_field;
+use crate::tcp::{helper::DateTime, Protocol, Tdx};
+
+/// 查询扩展行情品种的 K 线。对应于 pytdx 中的 exhq.get_instrument_bars、GetInstrumentBars。
+///
+/// category 的含义与 [`Kline`][crate::tcp::stock::Kline] 相同（比如 0 为 5 分钟 K 线、4 为日线）。
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct InstrumentBars<'d> {
+    pub send: Box<[u8]>,
+    pub market: u8,
+    pub code: &'d str,
+    pub category: u16,
+    pub start: u32,
+    pub count: u16,
+    pub response: Vec<u8>,
+    pub data: Vec<InstrumentBarData>,
+}
+
+/// 为了对应 [`InstrumentBars::SEND`] 的含义，以下默认值值得注意：
+/// 1. market 默认为 47，code 默认为 `IFL8`；
+/// 2. category 默认为 4 （日线）；
+/// 3. count 默认为 3。
+#[rustfmt::skip]
+impl<'d> Default for InstrumentBars<'d> {
+    fn default() -> Self {
+        Self { market: 47, code: "IFL8", category: 4, start: 0, count: 3,
+               send:     Self::SEND.into(),
+               response: Vec::new(),
+               data:     Vec::with_capacity(3), }
+    }
+}
+
+impl<'d> InstrumentBars<'d> {
+    /// market 为 [`ExMarketData::market`][super::ExMarketData::market]。
+    ///
+    /// 当 code 的字节长度超过 9 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
+    pub fn new(
+        market: u8,
+        code: &'d str,
+        category: u16,
+        start: u32,
+        count: u16,
+    ) -> crate::Result<Self> {
+        let mut bars = Self::default();
+        bars.market(market)
+            .code(code)?
+            .category(category)
+            .start(start)
+            .count(count);
+        Ok(bars)
+    }
+
+    /// 修改市场。
+    pub fn market(&mut self, market: u8) -> &mut Self {
+        self.market = market;
+        self.send[12] = market;
+        self
+    }
+
+    /// 修改品种。当代码不正确时，不能正常得到响应。
+    ///
+    /// 当 code 的字节长度超过 9 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        code_field(&mut self.send[13..22], code)?;
+        self.code = code;
+        Ok(self)
+    }
+
+    /// 修改 K 线类型。
+    pub fn category(&mut self, category: u16) -> &mut Self {
+        self.category = category;
+        self.send[22..24].copy_from_slice(&category.to_le_bytes());
+        self
+    }
+
+    /// 修改起始位置。
+    pub fn start(&mut self, start: u32) -> &mut Self {
+        self.start = start;
+        self.send[26..30].copy_from_slice(&start.to_le_bytes());
+        self
+    }
+
+    /// 修改查询数量。
+    pub fn count(&mut self, count: u16) -> &mut Self {
+        self.count = count;
+        self.send[30..32].copy_from_slice(&count.to_le_bytes());
+        self
+    }
+}
+
+impl<'d> Tdx for InstrumentBars<'d> {
+    type Item = [InstrumentBarData];
+
+    /// #47 IFL8# 最近三天日线的请求字节。长度为 32。
+    /// ```python
+    /// struct.pack("<B9sHHIH", bytes) # 后 20 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x01, 0x01, 0x08, 0x6a, 0x01, 0x01, 0x16, 0x00, 0x16, 0x00, 0xff, 0x23, 0x2f, 0x49, 0x46,
+        0x4c, 0x38, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x03, 0x00,
+    ];
+    const TAG: &'static str = "扩展K线";
+    const PROTOCOL: Protocol = Protocol::EXHQ;
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 18 字节未知（包括市场和代码），之后 2 字节表示 K 线数量，之后每 32 字节使用
+    /// [`InstrumentBarData::parse`] 解析。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        use crate::tcp::helper::need;
+        need(&v, 20)?;
+        let count = crate::bytes_helper::u16_from_le_bytes(&v, 18) as usize;
+        need(&v, 20 + count * InstrumentBarData::LEN)?;
+        self.data = v[20..]
+            .chunks_exact(InstrumentBarData::LEN)
+            .take(count)
+            .map(|bytes| InstrumentBarData::parse(bytes, self.category))
+            .collect();
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// [`InstrumentBars`] 的解析结果：一根 K 线。
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
+pub struct InstrumentBarData {
+    pub dt: DateTime,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// 持仓
+    pub position: u32,
+    /// 成交量
+    pub trade: u32,
+    /// 结算价
+    pub price: f64,
+    /// 与 position 为相同的 4 字节，以 f32 解读。对于港股等没有持仓的品种，此值为成交额。
+    pub amount: f64,
+}
+
+impl InstrumentBarData {
+    /// 每根 K 线的字节长度。
+    pub const LEN: usize = 32;
+
+    /// ```python
+    /// dt = bytes[:4] # 见 helper::datetime
+    /// (open, high, low, close, position, trade, price) = struct.unpack("<ffffIIf", bytes[4:32])
+    /// (amount,) = struct.unpack("<f", bytes[20:24]) # python 表示方式
+    /// ```
+    pub fn parse(bytes: &[u8], category: u16) -> Self {
+        use crate::{
+            bytes_helper::{f32_from_le_bytes, u32_from_le_bytes},
+            tcp::helper::datetime,
+        };
+
+        let f = |pos: usize| f32_from_le_bytes(bytes, pos) as f64;
+        Self {
+            dt: datetime(&bytes[..4], category),
+            open: f(4),
+            high: f(8),
+            low: f(12),
+            close: f(16),
+            position: u32_from_le_bytes(bytes, 20),
+            trade: u32_from_le_bytes(bytes, 24),
+            price: f(28),
+            amount: f(20),
+        }
+    }
+}
+
+#[test]
+fn bars_new_modify() -> crate::Result<()> {
+    let bars = InstrumentBars::new(47, "IFL8", 4, 0, 3)?;
+    let mut bars2 = InstrumentBars::new(31, "00700", 9, 100, 800)?;
+    bars2.market(47).code("IFL8")?.category(4).start(0).count(3);
+    assert_eq!(bars.send, InstrumentBars::default().send);
+    assert_eq!(bars2.send, bars.send);
+
+    // 代码超过 9 字节时返回错误，且不修改请求
+    assert!(InstrumentBars::new(47, "IFL8_TOO_LONG", 4, 0, 3).is_err());
+    assert!(bars2.code("IFL8_TOO_LONG").is_err());
+    assert_eq!((bars2.code, &bars2.send), ("IFL8", &bars.send));
+    Ok(())
+}
+
+#[test]
+fn parse() {
+    let mut arr = vec![0x2f];
+    arr.extend_from_slice(b"IFL8\0\0\0\0\0");
+    arr.extend_from_slice(&[0; 8]);
+    arr.extend_from_slice(&[0x02, 0x00]);
+    for (date, ohlc, position, trade, price) in [
+        (
+            20210923u32,
+            [3800.0f32, 3820.0, 3790.5, 3810.0],
+            150000u32,
+            45678u32,
+            3805.0f32,
+        ),
+        (
+            20210924,
+            [3810.0, 3830.0, 3800.0, 3825.5],
+            151000,
+            40000,
+            3820.0,
+        ),
+    ] {
+        arr.extend_from_slice(&date.to_le_bytes());
+        ohlc.iter()
+            .for_each(|f| arr.extend_from_slice(&f.to_le_bytes()));
+        arr.extend_from_slice(&position.to_le_bytes());
+        arr.extend_from_slice(&trade.to_le_bytes());
+        arr.extend_from_slice(&price.to_le_bytes());
+    }
+
+    let mut bars = InstrumentBars::default();
+    crate::tcp::tests::truncated(&mut bars, &arr);
+    bars.parse(arr).unwrap();
+    let data = bars.result();
+    assert_eq!(data.len(), 2);
+    assert_eq!(data[0].dt.to_u32(), 20210923);
+    assert_eq!(data[1].dt.to_u32(), 20210924);
+    assert_eq!(
+        (data[0].open, data[0].high, data[0].low, data[0].close),
+        (3800.0, 3820.0, 3790.5, 3810.0)
+    );
+    assert_eq!((data[0].position, data[0].trade), (150000, 45678));
+    assert_eq!(data[0].price, 3805.0);
+    assert_eq!(data[0].amount, f32::from_bits(150000) as f64);
+    assert_eq!((data[1].close, data[1].price), (3825.5, 3820.0));
+}
@@ -0,0 +1,233 @@
+use super::code_string;
+use crate::tcp::{helper::gbk_until_nul, Protocol, Tdx};
+
+/// 查询扩展行情的品种数量。对应于 pytdx 中的 exhq.get_instrument_count、GetInstrumentCount。
+#[derive(Debug, Clone)]
+pub struct InstrumentCount {
+    pub send: Box<[u8]>,
+    /// 响应的结果：品种数量
+    pub count: u32,
+}
+
+impl Default for InstrumentCount {
+    fn default() -> Self {
+        Self {
+            send: Self::SEND.into(),
+            count: 0,
+        }
+    }
+}
+
+impl Tdx for InstrumentCount {
+    type Item = u32;
+
+    /// 品种数量的请求字节。长度为 12。
+    const SEND: &'static [u8] = &[
+        0x01, 0x03, 0x48, 0x66, 0x00, 0x01, 0x02, 0x00, 0x02, 0x00, 0xf0, 0x23,
+    ];
+    const TAG: &'static str = "扩展品种数量";
+    const PROTOCOL: Protocol = Protocol::EXHQ;
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 19 字节未知，之后 4 字节表示品种数量。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        crate::tcp::helper::need(&v, 23)?;
+        self.count = crate::bytes_helper::u32_from_le_bytes(&v, 19);
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.count
+    }
+}
+
+/// 查询扩展行情的品种列表。对应于 pytdx 中的 exhq.get_instrument_info、GetInstrumentInfo。
+///
+/// 从 start 位置开始，最多返回 count 个品种；品种总数见 [`InstrumentCount`]。
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct InstrumentList {
+    pub send: Box<[u8]>,
+    pub start: u32,
+    pub count: u16,
+    pub response: Vec<u8>,
+    pub data: Vec<InstrumentInfo>,
+}
+
+/// 为了对应 [`InstrumentList::SEND`] 的含义，start 默认为 0，count 默认为 100。
+impl Default for InstrumentList {
+    fn default() -> Self {
+        Self {
+            send: Self::SEND.into(),
+            start: 0,
+            count: 100,
+            response: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+}
+
+impl InstrumentList {
+    pub fn new(start: u32, count: u16) -> Self {
+        let mut list = Self::default();
+        list.start(start).count(count);
+        list
+    }
+
+    /// 修改起始位置。
+    pub fn start(&mut self, start: u32) -> &mut Self {
+        self.start = start;
+        self.send[12..16].copy_from_slice(&start.to_le_bytes());
+        self
+    }
+
+    /// 修改查询数量。
+    pub fn count(&mut self, count: u16) -> &mut Self {
+        self.count = count;
+        self.send[16..18].copy_from_slice(&count.to_le_bytes());
+        self
+    }
+}
+
+impl Tdx for InstrumentList {
+    type Item = [InstrumentInfo];
+
+    /// 从 0 开始 100 个品种的请求字节。长度为 18。
+    /// ```python
+    /// struct.pack("<IH", bytes) # 后 6 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x01, 0x04, 0x48, 0x67, 0x00, 0x01, 0x08, 0x00, 0x08, 0x00, 0xf5, 0x23, 0x00, 0x00, 0x00,
+        0x00, 0x64, 0x00,
+    ];
+    const TAG: &'static str = "扩展品种列表";
+    const PROTOCOL: Protocol = Protocol::EXHQ;
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 4 字节为起始位置，之后 2 字节表示品种数量，之后每 64 字节使用
+    /// [`InstrumentInfo::parse`] 解析。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        use crate::tcp::helper::need;
+        need(&v, 6)?;
+        let count = crate::bytes_helper::u16_from_le_bytes(&v, 4) as usize;
+        need(&v, 6 + count * InstrumentInfo::LEN)?;
+        self.data = v[6..]
+            .chunks_exact(InstrumentInfo::LEN)
+            .take(count)
+            .map(InstrumentInfo::parse)
+            .collect();
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// [`InstrumentList`] 的解析结果：一个品种。
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct InstrumentInfo {
+    /// 品种类别
+    pub category: u8,
+    /// 市场代码，见 [`ExMarketData::market`][super::ExMarketData::market]
+    pub market: u8,
+    /// 品种代码，比如 `IFL8`、`00700`
+    pub code: String,
+    /// 品种名称
+    pub name: String,
+    /// 描述
+    pub desc: String,
+}
+
+impl InstrumentInfo {
+    /// 每个品种的字节长度。
+    pub const LEN: usize = 64;
+
+    /// ```python
+    /// (
+    ///     category,
+    ///     market,
+    ///     unused,
+    ///     code,
+    ///     name,     # GBK 编码，以 \x00 结尾
+    ///     desc,     # GBK 编码，以 \x00 结尾
+    /// ) = struct.unpack("<BB3s9s17s9s", bytes[:40]) # python 表示方式，之后 24 字节未知
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Self {
+        Self {
+            category: bytes[0],
+            market: bytes[1],
+            code: code_string(&bytes[5..14]),
+            name: gbk_until_nul(&bytes[14..31]),
+            desc: gbk_until_nul(&bytes[31..40]),
+        }
+    }
+}
+
+#[test]
+fn instrument_new_modify() {
+    assert_eq!(InstrumentList::LEN, 18);
+    let list = InstrumentList::new(0, 100);
+    let mut list2 = InstrumentList::new(500, 50);
+    list2.start(0).count(100);
+    assert_eq!(list.send, InstrumentList::default().send);
+    assert_eq!(list2.send, list.send);
+    assert_eq!(
+        &InstrumentList::new(0x0102_0304, 0x0506).send[12..],
+        &[0x04, 0x03, 0x02, 0x01, 0x06, 0x05]
+    );
+}
+
+#[test]
+fn parse() {
+    let mut arr = vec![0; 23];
+    arr[19..].copy_from_slice(&54321u32.to_le_bytes());
+    let mut count = InstrumentCount::default();
+    crate::tcp::tests::truncated(&mut count, &arr);
+    count.parse(arr).unwrap();
+    assert_eq!(*count.result(), 54321);
+
+    let mut arr = vec![0x00, 0x00, 0x00, 0x00, 0x02, 0x00];
+    for (category, market, code, name) in
+        [(3, 47, "IFL8", "沪深主连"), (2, 31, "00700", "腾讯控股")]
+    {
+        let mut chunk = [0; InstrumentInfo::LEN];
+        let name = encoding_rs::GBK.encode(name).0;
+        chunk[0] = category;
+        chunk[1] = market;
+        chunk[5..5 + code.len()].copy_from_slice(code.as_bytes());
+        chunk[14..14 + name.len()].copy_from_slice(&name);
+        arr.extend_from_slice(&chunk);
+    }
+    let mut list = InstrumentList::default();
+    crate::tcp::tests::truncated(&mut list, &arr);
+    list.parse(arr).unwrap();
+    assert_eq!(
+        list.result(),
+        [
+            InstrumentInfo {
+                category: 3,
+                market: 47,
+                code: "IFL8".into(),
+                name: "沪深主连".into(),
+                desc: "".into()
+            },
+            InstrumentInfo {
+                category: 2,
+                market: 31,
+                code: "00700".into(),
+                name: "腾讯控股".into(),
+                desc: "".into()
+            },
+        ]
+    );
+}
@@ -0,0 +1,137 @@
+use crate::tcp::{helper::gbk_until_nul, Protocol, Tdx};
+
+/// 查询扩展行情的市场列表。对应于 pytdx 中的 exhq.get_markets、GetMarkets。
+#[derive(Debug, Clone)]
+pub struct ExMarkets {
+    pub send: Box<[u8]>,
+    /// 响应信息中的市场数量。
+    pub count: usize,
+    pub response: Vec<u8>,
+    pub data: Vec<ExMarketData>,
+}
+
+impl Default for ExMarkets {
+    fn default() -> Self {
+        Self {
+            send: Self::SEND.into(),
+            count: 0,
+            response: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+}
+
+impl Tdx for ExMarkets {
+    type Item = [ExMarketData];
+
+    /// 市场列表的请求字节。长度为 12。
+    const SEND: &'static [u8] = &[
+        0x01, 0x02, 0x48, 0x69, 0x00, 0x01, 0x02, 0x00, 0x02, 0x00, 0xf4, 0x23,
+    ];
+    const TAG: &'static str = "扩展市场列表";
+    const PROTOCOL: Protocol = Protocol::EXHQ;
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 2 字节表示市场数量，之后每 64 字节使用 [`ExMarketData::parse`] 解析。
+    ///
+    /// 与 pytdx 相同，舍弃 category 和 market 都为 0 的空白记录，所以结果的数量可能少于
+    /// [`count`][ExMarkets::count]。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        use crate::tcp::helper::need;
+        need(&v, 2)?;
+        self.count = crate::bytes_helper::u16_from_le_bytes(&v, 0) as usize;
+        need(&v, 2 + self.count * ExMarketData::LEN)?;
+        self.data = v[2..]
+            .chunks_exact(ExMarketData::LEN)
+            .take(self.count)
+            .map(ExMarketData::parse)
+            .filter(|m| m.category != 0 || m.market != 0)
+            .collect();
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// [`ExMarkets`] 的解析结果：一个市场。
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ExMarketData {
+    /// 市场类别
+    pub category: u8,
+    /// 市场名称，比如 `郑州商品`
+    pub name: String,
+    /// 市场代码，即其他扩展行情请求中的 market 参数
+    pub market: u8,
+    /// 市场简称，比如 `QZ`
+    pub short_name: String,
+}
+
+impl ExMarketData {
+    /// 每个市场的字节长度。
+    pub const LEN: usize = 64;
+
+    /// ```python
+    /// (
+    ///     category,
+    ///     name,       # GBK 编码，以 \x00 结尾
+    ///     market,
+    ///     short_name, # GBK 编码
+    ///     unknown,
+    /// ) = struct.unpack("<B32sB2s28s", bytes) # python 表示方式
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Self {
+        Self {
+            category: bytes[0],
+            name: gbk_until_nul(&bytes[1..33]),
+            market: bytes[33],
+            short_name: gbk_until_nul(&bytes[34..36]),
+        }
+    }
+}
+
+#[test]
+fn parse() {
+    // 第二条为空白记录
+    let mut arr = vec![0x03, 0x00];
+    for (category, name, market, short_name) in [
+        (1, "临时股", 1, "TP"),
+        (0, "", 0, "\0\0"),
+        (4, "郑州商品", 28, "QZ"),
+    ] {
+        let mut chunk = [0; ExMarketData::LEN];
+        let name = encoding_rs::GBK.encode(name).0;
+        chunk[0] = category;
+        chunk[1..1 + name.len()].copy_from_slice(&name);
+        chunk[33] = market;
+        chunk[34..36].copy_from_slice(short_name.as_bytes());
+        arr.extend_from_slice(&chunk);
+    }
+
+    let mut markets = ExMarkets::default();
+    crate::tcp::tests::truncated(&mut markets, &arr);
+    markets.parse(arr).unwrap();
+    assert_eq!(markets.count, 3);
+    assert_eq!(
+        markets.result(),
+        [
+            ExMarketData {
+                category: 1,
+                name: "临时股".into(),
+                market: 1,
+                short_name: "TP".into()
+            },
+            ExMarketData {
+                category: 4,
+                name: "郑州商品".into(),
+                market: 28,
+                short_name: "QZ".into()
+            },
+        ]
+    );
+}
@@ -0,0 +1,170 @@
+use super::code_field;
+use crate::tcp::{Protocol, Tdx};
+
+/// 查询扩展行情品种的当日分时数据。对应于 pytdx 中的 exhq.get_minute_time_data、
+/// GetMinuteTimeData。
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct InstrumentMinute<'d> {
+    pub send: Box<[u8]>,
+    pub market: u8,
+    pub code: &'d str,
+    /// 响应信息中的分时点数量。
+    pub count: usize,
+    pub response: Vec<u8>,
+    pub data: Vec<InstrumentMinuteData>,
+}
+
+/// 为了对应 [`InstrumentMinute::SEND`] 的含义，market 默认为 47，code 默认为 `IFL8`。
+#[rustfmt::skip]
+impl<'d> Default for InstrumentMinute<'d> {
+    fn default() -> Self {
+        Self { market: 47, code: "IFL8", count: 0,
+               send:     Self::SEND.into(),
+               response: Vec::new(),
+               data:     Vec::new(), }
+    }
+}
+
+impl<'d> InstrumentMinute<'d> {
+    /// market 为 [`ExMarketData::market`][super::ExMarketData::market]。
+    ///
+    /// 当 code 的字节长度超过 9 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
+    pub fn new(market: u8, code: &'d str) -> crate::Result<Self> {
+        let mut minute = Self::default();
+        minute.market(market).code(code)?;
+        Ok(minute)
+    }
+
+    /// 修改市场。
+    pub fn market(&mut self, market: u8) -> &mut Self {
+        self.market = market;
+        self.send[12] = market;
+        self
+    }
+
+    /// 修改品种。当代码不正确时，不能正常得到响应。
+    ///
+    /// 当 code 的字节长度超过 9 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        code_field(&mut self.send[13..22], code)?;
+        self.code = code;
+        Ok(self)
+    }
+}
+
+impl<'d> Tdx for InstrumentMinute<'d> {
+    type Item = [InstrumentMinuteData];
+
+    /// #47 IFL8# 当日分时数据的请求字节。长度为 22。
+    /// ```python
+    /// struct.pack("<B9s", bytes) # 后 10 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x01, 0x07, 0x08, 0x00, 0x01, 0x01, 0x0c, 0x00, 0x0c, 0x00, 0x0b, 0x24, 0x2f, 0x49, 0x46,
+        0x4c, 0x38, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    const TAG: &'static str = "扩展分时";
+    const PROTOCOL: Protocol = Protocol::EXHQ;
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 10 字节为市场和代码，之后 2 字节表示分时点数量，之后每 18 字节使用
+    /// [`InstrumentMinuteData::parse`] 解析。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        use crate::tcp::helper::need;
+        need(&v, 12)?;
+        let count = crate::bytes_helper::u16_from_le_bytes(&v, 10) as usize;
+        need(&v, 12 + count * InstrumentMinuteData::LEN)?;
+        self.data = v[12..]
+            .chunks_exact(InstrumentMinuteData::LEN)
+            .take(count)
+            .map(InstrumentMinuteData::parse)
+            .collect();
+        self.count = count;
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// [`InstrumentMinute`] 的解析结果：一个分时点。
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
+pub struct InstrumentMinuteData {
+    /// 分钟数，即从 0 点开始的分钟数，比如 `571` 表示 9:31。
+    pub time: u16,
+    pub price: f64,
+    /// 均价
+    pub avg_price: f64,
+    /// 成交量
+    pub vol: u32,
+    pub amount: u32,
+}
+
+impl InstrumentMinuteData {
+    /// 每个分时点的字节长度。
+    pub const LEN: usize = 18;
+
+    /// ```python
+    /// (time, price, avg_price, vol, amount) = struct.unpack("<HffII", bytes) # python 表示方式
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Self {
+        use crate::bytes_helper::{f32_from_le_bytes, u16_from_le_bytes, u32_from_le_bytes};
+        Self {
+            time: u16_from_le_bytes(bytes, 0),
+            price: f32_from_le_bytes(bytes, 2) as f64,
+            avg_price: f32_from_le_bytes(bytes, 6) as f64,
+            vol: u32_from_le_bytes(bytes, 10),
+            amount: u32_from_le_bytes(bytes, 14),
+        }
+    }
+}
+
+#[test]
+fn minute_new_modify() -> crate::Result<()> {
+    let minute = InstrumentMinute::new(47, "IFL8")?;
+    let mut minute2 = InstrumentMinute::new(31, "00700")?;
+    minute2.market(47).code("IFL8")?;
+    assert_eq!(minute.send, InstrumentMinute::default().send);
+    assert_eq!(minute2.send, minute.send);
+
+    // 代码超过 9 字节时返回错误，且不修改请求
+    assert!(InstrumentMinute::new(47, "IFL8_TOO_LONG").is_err());
+    assert!(minute2.code("IFL8_TOO_LONG").is_err());
+    assert_eq!((minute2.code, &minute2.send), ("IFL8", &minute.send));
+    Ok(())
+}
+
+#[test]
+fn parse() {
+    #[rustfmt::skip]
+    let res = [
+        InstrumentMinuteData { time: 571, price: 3810.0, avg_price: 3810.0, vol: 120, amount: 0 },
+        InstrumentMinuteData { time: 572, price: 3812.5, avg_price: 3811.25, vol: 80, amount: 0 },
+    ];
+
+    let mut arr = vec![0x2f];
+    arr.extend_from_slice(b"IFL8\0\0\0\0\0");
+    arr.extend_from_slice(&[0x02, 0x00]);
+    for d in &res {
+        arr.extend_from_slice(&d.time.to_le_bytes());
+        arr.extend_from_slice(&(d.price as f32).to_le_bytes());
+        arr.extend_from_slice(&(d.avg_price as f32).to_le_bytes());
+        arr.extend_from_slice(&d.vol.to_le_bytes());
+        arr.extend_from_slice(&d.amount.to_le_bytes());
+    }
+
+    let mut minute = InstrumentMinute::default();
+    crate::tcp::tests::truncated(&mut minute, &arr);
+    minute.parse(arr).unwrap();
+    assert_eq!(minute.count, 2);
+    assert_eq!(minute.result(), res);
+}
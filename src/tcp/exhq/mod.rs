@@ -0,0 +1,161 @@
+//! 扩展行情（期货、期权、港股、基金等），对应于 pytdx 中的 TdxExHq_API 。
+//!
+//! 扩展行情服务器（见 [`EX_IP`][super::ip::EX_IP]，端口通常为 7727）与标准行情服务器的
+//! 握手包不同，所以使用 [`connect`] 建立连接；之后的请求与标准行情相同，通过 [`Tdx`][super::Tdx]
+//! trait 发送和解析，与标准行情共用 [`send_recv_decompress`][super::send_recv_decompress]
+//! 以及响应信息的检查。本模块的类型的 [`Tdx::PROTOCOL`][super::Tdx::PROTOCOL] 均为
+//! [`Protocol::EXHQ`]，异步的 `AsyncTcp` 同样按照它握手。
+//!
+//! ```no_run
+//! use rustdx::tcp::{exhq, Tdx};
+//!
+//! let mut tcp = exhq::connect(&rustdx::tcp::ip::EX_IP[0])?;
+//! let markets = exhq::ExMarkets::default().recv_parsed(&mut tcp)?.to_vec();
+//! let quote = exhq::InstrumentQuote::new(47, "IFL8")?.recv_parsed(&mut tcp)?.clone();
+//! # Ok::<(), rustdx::Error>(())
+//! ```
+
+use super::{Protocol, Tcp, TIMEOUT};
+use crate::Result;
+use std::{net::SocketAddr, time::Duration};
+
+mod bars;
+pub use bars::{InstrumentBarData, InstrumentBars};
+
+mod instrument;
+pub use instrument::{InstrumentCount, InstrumentInfo, InstrumentList};
+
+mod market;
+pub use market::{ExMarketData, ExMarkets};
+
+mod minute;
+pub use minute::{InstrumentMinute, InstrumentMinuteData};
+
+mod quote;
+pub use quote::{InstrumentQuote, InstrumentQuoteData};
+
+/// 扩展行情的握手包。对应于 pytdx 中的 ExSetupCmd1 。
+pub const EX_SETUP: &[u8] = &[
+    0x01, 0x01, 0x48, 0x65, 0x00, 0x01, 0x52, 0x00, 0x52, 0x00, 0x54, 0x24, 0x1f, 0x32, 0xc6, 0xe5,
+    0xd5, 0x3d, 0xfb, 0x41, 0x1f, 0x32, 0xc6, 0xe5, 0xd5, 0x3d, 0xfb, 0x41, 0x1f, 0x32, 0xc6, 0xe5,
+    0xd5, 0x3d, 0xfb, 0x41, 0x1f, 0x32, 0xc6, 0xe5, 0xd5, 0x3d, 0xfb, 0x41, 0x1f, 0x32, 0xc6, 0xe5,
+    0xd5, 0x3d, 0xfb, 0x41, 0x1f, 0x32, 0xc6, 0xe5, 0xd5, 0x3d, 0xfb, 0x41, 0x1f, 0x32, 0xc6, 0xe5,
+    0xd5, 0x3d, 0xfb, 0x41, 0x1f, 0x32, 0xc6, 0xe5, 0xd5, 0x3d, 0xfb, 0x41, 0xcc, 0xe1, 0x6d, 0xff,
+    0xd5, 0xba, 0x3f, 0xb8, 0xcb, 0xc5, 0x7a, 0x05, 0x4f, 0x77, 0x48, 0xea,
+];
+
+/// 扩展行情中代码的最大字节长度。
+pub const CODE_LEN: usize = 9;
+
+/// 连接扩展行情服务器，并发送握手包 [`EX_SETUP`]。超时值为 [`TIMEOUT`]。
+pub fn connect(ip: &SocketAddr) -> Result<Tcp> {
+    connect_timeout(ip, TIMEOUT)
+}
+
+/// 连接扩展行情服务器，并发送握手包 [`EX_SETUP`]。连接、读取和写入的超时值均为 timeout 。
+///
+/// 与 `Tcp::new_with_protocol(ip, timeout, Protocol::EXHQ)` 相同。
+pub fn connect_timeout(ip: &SocketAddr, timeout: Duration) -> Result<Tcp> {
+    Tcp::new_with_protocol(ip, timeout, Protocol::EXHQ)
+}
+
+/// 把代码写入请求字节中 [`CODE_LEN`] 字节的字段，不足的部分补 0 。
+///
+/// 当 code 的字节长度超过 [`CODE_LEN`] 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+/// 且不修改任何内容。
+fn code_field(field: &mut [u8], code: &str) -> Result<()> {
+    if code.len() > CODE_LEN {
+        return Err(crate::Error::InvalidCode(code.into()));
+    }
+    field[..CODE_LEN].fill(0);
+    field[..code.len()].copy_from_slice(code.as_bytes());
+    Ok(())
+}
+
+/// 解析响应中 [`CODE_LEN`] 字节的代码字段，舍弃末尾的 `\0`。
+fn code_string(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+#[test]
+fn connect_and_recv() -> Result<()> {
+    use super::{mock::Fixture, tests::MockServer, Tdx};
+
+    let mut fixture = Fixture::default();
+    fixture.insert_data(EX_SETUP, &[]);
+    fixture.insert_data(InstrumentCount::SEND, &{
+        let mut v = [0; 23];
+        v[19..].copy_from_slice(&54321u32.to_le_bytes());
+        v
+    });
+    let server = MockServer::start(fixture)?;
+    let mut tcp = connect(&server.addr)?;
+    assert_eq!(*InstrumentCount::default().recv_parsed(&mut tcp)?, 54321);
+    assert_eq!(server.requests(), 2);
+
+    // 标准行情的请求不能通过扩展行情的连接发送
+    let err = super::Heartbeat::new(0).recv_parsed(&mut tcp).unwrap_err();
+    assert!(matches!(err, crate::Error::Invalid { .. }), "{err}");
+    assert_eq!(server.requests(), 2);
+
+    // 标准行情的握手包没有记录，连接会被关闭
+    assert!(Tcp::new_with_ip(&server.addr).is_err());
+    Ok(())
+}
+
+/// 扩展行情与标准行情一样检查响应信息；握手时发现服务器不原样返回序号和消息类型，则不再检查。
+#[test]
+fn header_check() -> Result<()> {
+    use super::{
+        mock::{frame, Fixture},
+        tests::MockServer,
+        Tdx,
+    };
+
+    let data = {
+        let mut v = [0; 23];
+        v[19..].copy_from_slice(&54321u32.to_le_bytes());
+        v
+    };
+    // 迟到的响应：InstrumentCount 得到的是 ExMarkets 的响应
+    let mut fixture = Fixture::default();
+    fixture.insert_data(EX_SETUP, &[]);
+    fixture.insert(InstrumentCount::SEND, frame(ExMarkets::SEND, &data));
+    let server = MockServer::start(fixture)?;
+    let mut tcp = connect(&server.addr)?;
+    assert!(tcp.check_header());
+    let err = InstrumentCount::default()
+        .recv_parsed(&mut tcp)
+        .unwrap_err();
+    assert!(
+        matches!(err, crate::Error::ResponseMismatch { .. }),
+        "{err}"
+    );
+
+    // 响应信息中的序号和消息类型都为 0
+    let mut fixture = Fixture::default();
+    fixture.insert(EX_SETUP, frame(&[], &[]));
+    fixture.insert(InstrumentCount::SEND, frame(&[], &data));
+    let server = MockServer::start(fixture)?;
+    let mut tcp = connect(&server.addr)?;
+    assert!(!tcp.check_header());
+    assert_eq!(*InstrumentCount::default().recv_parsed(&mut tcp)?, 54321);
+    Ok(())
+}
+
+#[test]
+fn code_field_string() {
+    let mut field = [0xff; 12];
+    code_field(&mut field, "IFL8").unwrap();
+    assert_eq!(&field, b"IFL8\0\0\0\0\0\xff\xff\xff");
+    assert_eq!(code_string(&field[..CODE_LEN]), "IFL8");
+    code_field(&mut field, "123456789").unwrap();
+    assert_eq!(code_string(&field[..CODE_LEN]), "123456789");
+    let err = code_field(&mut field, "1234567890").unwrap_err();
+    assert!(
+        matches!(err, crate::Error::InvalidCode(ref c) if c == "1234567890"),
+        "{err}"
+    );
+    assert_eq!(code_string(&field[..CODE_LEN]), "123456789");
+}
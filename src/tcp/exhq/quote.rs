@@ -0,0 +1,234 @@
+use super::{code_field, code_string};
+use crate::tcp::{Protocol, Tdx};
+
+/// 查询扩展行情品种的实时行情。对应于 pytdx 中的 exhq.get_instrument_quote、GetInstrumentQuote。
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct InstrumentQuote<'d> {
+    pub send: Box<[u8]>,
+    pub market: u8,
+    pub code: &'d str,
+    pub response: Vec<u8>,
+    pub data: InstrumentQuoteData,
+}
+
+/// 为了对应 [`InstrumentQuote::SEND`] 的含义，market 默认为 47（中金所期货），code 默认为
+/// `IFL8`（沪深 300 股指期货主连）。
+impl<'d> Default for InstrumentQuote<'d> {
+    fn default() -> Self {
+        Self {
+            send: Self::SEND.into(),
+            market: 47,
+            code: "IFL8",
+            response: Vec::new(),
+            data: InstrumentQuoteData::default(),
+        }
+    }
+}
+
+impl<'d> InstrumentQuote<'d> {
+    /// market 为 [`ExMarketData::market`][super::ExMarketData::market]。
+    ///
+    /// 当 code 的字节长度超过 9 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
+    pub fn new(market: u8, code: &'d str) -> crate::Result<Self> {
+        let mut quote = Self::default();
+        quote.market(market).code(code)?;
+        Ok(quote)
+    }
+
+    /// 修改市场。
+    pub fn market(&mut self, market: u8) -> &mut Self {
+        self.market = market;
+        self.send[12] = market;
+        self
+    }
+
+    /// 修改品种。当代码不正确时，不能正常得到响应。
+    ///
+    /// 当 code 的字节长度超过 9 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        code_field(&mut self.send[13..22], code)?;
+        self.code = code;
+        Ok(self)
+    }
+}
+
+impl<'d> Tdx for InstrumentQuote<'d> {
+    type Item = InstrumentQuoteData;
+
+    /// #47 IFL8# 实时行情的请求字节。长度为 22。
+    /// ```python
+    /// struct.pack("<B9s", bytes) # 后 10 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x01, 0x01, 0x08, 0x02, 0x02, 0x01, 0x0c, 0x00, 0x0c, 0x00, 0xfa, 0x23, 0x2f, 0x49, 0x46,
+        0x4c, 0x38, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    const TAG: &'static str = "扩展实时行情";
+    const PROTOCOL: Protocol = Protocol::EXHQ;
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 10 字节为市场和代码，跳过 4 字节未知数据之后，为 136 字节的行情数据。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        crate::tcp::helper::need(&v, InstrumentQuoteData::LEN)?;
+        self.data = InstrumentQuoteData::parse(&v);
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// [`InstrumentQuote`] 的解析结果。
+///
+/// 字段与 [`QuotesData`][crate::tcp::stock::QuotesData] 对应，另有期货特有的开仓和持仓。
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
+pub struct InstrumentQuoteData {
+    pub market: u8,
+    pub code: String,
+    /// 现价
+    pub price: f64,
+    /// 昨收
+    pub preclose: f64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    /// 开仓
+    pub kaicang: u32,
+    /// 总量
+    pub vol: u32,
+    /// 现量
+    pub cur_vol: u32,
+    /// 内盘
+    pub s_vol: u32,
+    /// 外盘
+    pub b_vol: u32,
+    /// 持仓
+    pub chicang: u32,
+    /// 买一至买五的价格
+    pub bid: [f64; 5],
+    /// 卖一至卖五的价格
+    pub ask: [f64; 5],
+    /// 买一至买五的挂单量
+    pub bid_vol: [u32; 5],
+    /// 卖一至卖五的挂单量
+    pub ask_vol: [u32; 5],
+}
+
+impl InstrumentQuoteData {
+    /// 响应的字节长度。
+    pub const LEN: usize = 150;
+
+    /// ```python
+    /// market, code = struct.unpack("<B9s", bytes[:10])
+    /// (
+    ///     preclose, open, high, low, price,
+    ///     kaicang, _, vol, cur_vol, _, s_vol, b_vol, _, chicang,
+    ///     bid1, ..., bid5, bid_vol1, ..., bid_vol5,
+    ///     ask1, ..., ask5, ask_vol1, ..., ask_vol5,
+    /// ) = struct.unpack("<fffffIIIIIIIIIfffffIIIIIfffffIIIII", bytes[14:150]) # python 表示方式
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Self {
+        use crate::bytes_helper::{f32_from_le_bytes, u32_from_le_bytes};
+
+        let f = |pos: usize| f32_from_le_bytes(bytes, 14 + pos * 4) as f64;
+        let u = |pos: usize| u32_from_le_bytes(bytes, 14 + pos * 4);
+        Self {
+            market: bytes[0],
+            code: code_string(&bytes[1..10]),
+            preclose: f(0),
+            open: f(1),
+            high: f(2),
+            low: f(3),
+            price: f(4),
+            kaicang: u(5),
+            vol: u(7),
+            cur_vol: u(8),
+            s_vol: u(10),
+            b_vol: u(11),
+            chicang: u(13),
+            bid: std::array::from_fn(|i| f(14 + i)),
+            bid_vol: std::array::from_fn(|i| u(19 + i)),
+            ask: std::array::from_fn(|i| f(24 + i)),
+            ask_vol: std::array::from_fn(|i| u(29 + i)),
+        }
+    }
+}
+
+#[test]
+fn quote_new_modify() -> crate::Result<()> {
+    let quote = InstrumentQuote::new(47, "IFL8")?;
+    let mut quote2 = InstrumentQuote::new(31, "00700")?;
+    quote2.market(47).code("IFL8")?;
+    assert_eq!(quote.send, InstrumentQuote::default().send);
+    assert_eq!(quote2.send, quote.send);
+
+    // 代码超过 9 字节时返回错误，且不修改请求
+    assert!(InstrumentQuote::new(47, "IFL8_TOO_LONG").is_err());
+    assert!(quote2.code("IFL8_TOO_LONG").is_err());
+    assert_eq!((quote2.code, &quote2.send), ("IFL8", &quote.send));
+    Ok(())
+}
+
+#[test]
+fn parse() {
+    let mut arr = vec![0x2f];
+    arr.extend_from_slice(b"IFL8\0\0\0\0\0");
+    arr.extend_from_slice(&[0; 4]);
+    for f in [3800.0f32, 3802.5, 3820.0, 3790.5, 3810.0] {
+        arr.extend_from_slice(&f.to_le_bytes());
+    }
+    for u in [120u32, 0, 45678, 12, 0, 22000, 23678, 0, 150000] {
+        arr.extend_from_slice(&u.to_le_bytes());
+    }
+    for (prices, vols) in [
+        (
+            [3809.5f32, 3809.0, 3808.5, 3808.0, 3807.5],
+            [5u32, 6, 7, 8, 9],
+        ),
+        (
+            [3810.5, 3811.0, 3811.5, 3812.0, 3812.5],
+            [10, 11, 12, 13, 14],
+        ),
+    ] {
+        prices
+            .iter()
+            .for_each(|f| arr.extend_from_slice(&f.to_le_bytes()));
+        vols.iter()
+            .for_each(|u| arr.extend_from_slice(&u.to_le_bytes()));
+    }
+
+    let mut quote = InstrumentQuote::default();
+    crate::tcp::tests::truncated(&mut quote, &arr);
+    quote.parse(arr).unwrap();
+    assert_eq!(
+        *quote.result(),
+        InstrumentQuoteData {
+            market: 47,
+            code: "IFL8".into(),
+            price: 3810.0,
+            preclose: 3800.0,
+            open: 3802.5,
+            high: 3820.0,
+            low: 3790.5,
+            kaicang: 120,
+            vol: 45678,
+            cur_vol: 12,
+            s_vol: 22000,
+            b_vol: 23678,
+            chicang: 150000,
+            bid: [3809.5, 3809.0, 3808.5, 3808.0, 3807.5],
+            ask: [3810.5, 3811.0, 3811.5, 3812.0, 3812.5],
+            bid_vol: [5, 6, 7, 8, 9],
+            ask_vol: [10, 11, 12, 13, 14],
+        }
+    );
+}
@@ -0,0 +1,186 @@
+//! 响应信息的解码和检查。
+
+use super::RECV_SIZE;
+use crate::{
+    bytes_helper::{u16_from_le_bytes, u32_from_le_bytes},
+    Error, Result,
+};
+
+/// 响应信息：每个响应的前 16 字节。
+///
+/// | 字节    | 含义                                   |
+/// | ------- | -------------------------------------- |
+/// | 0 ~ 3   | 魔数 [`ResponseHeader::MAGIC`]          |
+/// | 4       | 未知（通常为 0x0c）                    |
+/// | 5 ~ 8   | 序号，与请求的 1 ~ 4 字节相同          |
+/// | 9       | 未知                                   |
+/// | 10 ~ 11 | 消息类型，与请求的 10 ~ 11 字节相同    |
+/// | 12 ~ 13 | 解压前的长度                           |
+/// | 14 ~ 15 | 解压后的长度（与解压前相等表示未压缩） |
+///
+/// 读取超时之后，上一次请求的响应可能迟到，被当成下一次请求的响应。
+/// [`send_recv`][super::send_recv] 使用 [`ResponseHeader::check`] 对比序号和消息类型，
+/// 以发现这种错位的响应。注意：同一种请求的序号是固定的，所以无法区分两次相同类型的请求。
+///
+/// 序号和消息类型的位置与 pytdx 等实现对协议的解读一致，但还没有真实服务器的录制响应。
+/// 为了避免服务器不原样返回时每个请求都失败，连接在握手时验证这一点：第一个握手包的响应不一致时，
+/// 此连接不再检查（见 [`check_handshake`][super::check_handshake]）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseHeader {
+    pub magic: u32,
+    /// 序号
+    pub seq: u32,
+    /// 消息类型
+    pub msg_type: u16,
+    /// 解压前的长度，即响应信息之后的字节数
+    pub deflate_size: u16,
+    /// 解压后的长度
+    pub inflate_size: u16,
+}
+
+impl ResponseHeader {
+    pub const MAGIC: u32 = 0x0074_cbb1;
+
+    /// 解码响应信息，不做任何检查。
+    pub fn new(bytes: &[u8; RECV_SIZE]) -> Self {
+        Self {
+            magic: u32_from_le_bytes(bytes, 0),
+            seq: u32_from_le_bytes(bytes, 5),
+            msg_type: u16_from_le_bytes(bytes, 10),
+            deflate_size: u16_from_le_bytes(bytes, 12),
+            inflate_size: u16_from_le_bytes(bytes, 14),
+        }
+    }
+
+    /// 解码响应信息。魔数不正确时返回 [`Error::BadHeader`]。
+    pub fn parse(bytes: &[u8; RECV_SIZE]) -> Result<Self> {
+        let header = Self::new(bytes);
+        if header.magic != Self::MAGIC {
+            return Err(Error::BadHeader(bytes.to_vec()));
+        }
+        Ok(header)
+    }
+
+    /// 请求字节中的序号和消息类型。请求不足 12 字节时返回 None 。
+    pub fn request_id(request: &[u8]) -> Option<(u32, u16)> {
+        (request.len() >= 12).then(|| {
+            (
+                u32_from_le_bytes(request, 1),
+                u16_from_le_bytes(request, 10),
+            )
+        })
+    }
+
+    /// 检查此响应是否对应于 request ：序号和消息类型不一致时返回 [`Error::ResponseMismatch`]。
+    ///
+    /// request 不足 12 字节时不做检查。
+    pub fn check(&self, request: &[u8]) -> Result<()> {
+        match Self::request_id(request) {
+            Some(expected) if expected != (self.seq, self.msg_type) => {
+                Err(Error::ResponseMismatch {
+                    expected,
+                    found: (self.seq, self.msg_type),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn response_header() {
+    use super::stock::Kline;
+    use super::Tdx;
+
+    let bytes = [
+        0xb1, 0xcb, 0x74, 0x00, 0x0c, 0x01, 0x08, 0x64, 0x01, 0x00, 0x2d, 0x05, 0x1a, 0x00, 0x3f,
+        0x00,
+    ];
+    let header = ResponseHeader::parse(&bytes).unwrap();
+    assert_eq!(
+        header,
+        ResponseHeader {
+            magic: ResponseHeader::MAGIC,
+            seq: 0x0164_0801,
+            msg_type: 0x052d,
+            deflate_size: 26,
+            inflate_size: 63,
+        }
+    );
+    header.check(Kline::SEND).unwrap();
+    header.check(&[0x0c]).unwrap();
+
+    let err = header.check(super::PACK1).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::ResponseMismatch {
+            expected: (0x0093_1802, 0x000d),
+            found: (0x0164_0801, 0x052d)
+        }
+    ));
+
+    let mut bad = bytes;
+    bad[0] = 0;
+    assert!(matches!(
+        ResponseHeader::parse(&bad),
+        Err(Error::BadHeader(b)) if b == bad
+    ));
+}
+
+#[test]
+fn server_without_echo() -> Result<()> {
+    use super::{
+        mock::{frame, Fixture, MockServer},
+        Heartbeat, Tcp, Tdx, PACK1, PACK2, PACK3,
+    };
+
+    // 响应信息中的序号和消息类型都为 0 ：握手时发现之后，此连接不再检查，请求仍然成功
+    let mut fixture = Fixture::default();
+    for pack in [PACK1, PACK2, PACK3] {
+        fixture.insert(pack, frame(&[], &[]));
+    }
+    fixture.insert(Heartbeat::SEND, frame(&[], &[0x10, 0x27]));
+    let server = MockServer::start(fixture)?;
+    let mut tcp = Tcp::new_with_ip(&server.addr)?;
+    assert!(!tcp.check_header());
+    assert_eq!(*Heartbeat::new(0).recv_parsed(&mut tcp)?, 10000);
+    let err = super::batch::recv_batch(&mut tcp, &mut [Heartbeat::new(0)], 1).unwrap_err();
+    assert!(matches!(err, Error::Invalid { .. }), "{err:?}");
+
+    // 原样返回的服务器：检查保持开启
+    let server = MockServer::start(Fixture::handshake())?;
+    assert!(Tcp::new_with_ip(&server.addr)?.check_header());
+
+    // 只有第一个握手包可以用于判断；之后的握手包不一致时仍然返回错误
+    let mut fixture = Fixture::handshake();
+    fixture.insert(PACK2, frame(PACK1, &[]));
+    let server = MockServer::start(fixture)?;
+    let err = Tcp::new_with_ip(&server.addr).unwrap_err();
+    assert!(matches!(err, Error::ResponseMismatch { .. }), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn mismatched_response() -> Result<()> {
+    use super::{
+        mock::{frame, Fixture, MockServer},
+        stock::Kline,
+        Heartbeat, Tcp, Tdx,
+    };
+
+    // Kline 请求得到的是心跳包的响应
+    let mut fixture = Fixture::handshake();
+    fixture.insert(Kline::SEND, frame(Heartbeat::SEND, &[0x10, 0x27]));
+    let server = MockServer::start(fixture)?;
+    let mut tcp = Tcp::new_with_ip(&server.addr)?;
+    let err = Kline::default().recv_parsed(&mut tcp).unwrap_err();
+    assert!(matches!(err, Error::ResponseMismatch { .. }), "{err:?}");
+
+    let header = tcp.header();
+    assert_eq!(
+        Some((header.seq, header.msg_type)),
+        ResponseHeader::request_id(Heartbeat::SEND)
+    );
+    assert_eq!((header.deflate_size, header.inflate_size), (2, 2));
+    Ok(())
+}
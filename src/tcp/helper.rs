@@ -1,13 +1,18 @@
 //! 对应于 pytdx/helper.py 文件，用于辅助解析响应的字节数据。
 
-use crate::bytes_helper::{u16_from_le_bytes, u32_from_le_bytes};
+use crate::{
+    bytes_helper::{u16_from_le_bytes, u32_from_le_bytes},
+    Error, Result,
+};
 
 /// 解析日期时间的原始结果。如果需要其他形式的日期时间，可自行转化。
 ///
 /// 由函数 [`datetime`] 解析响应字节得到此结构体。
 ///
 /// 注意：默认 15 时（即 `DateTime::default().hour == 15`）。
-#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+///
+/// 字段按年、月、日、时、分的顺序比较，所以 [`Ord`] 即时间先后顺序。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
 pub struct DateTime {
     pub year: u16,
     pub month: u16,
@@ -69,28 +74,93 @@ pub fn datetime(arr: &[u8], category: u16) -> DateTime {
 ///
 /// 注意：
 /// 1. 第二次之后计算的价格为浮动价格，基于第一次解析的实际价格而浮动；
-/// 2. 返回的 pos 是不定长的。
-pub fn price(arr: &[u8], pos: &mut usize) -> i32 {
+/// 2. 返回的 pos 是不定长的；
+/// 3. 字节在编码结束之前用完时，返回 [`Error::ShortFrame`]。
+pub fn price(arr: &[u8], pos: &mut usize) -> Result<i32> {
+    let byte = |pos: usize| {
+        arr.get(pos)
+            .map(|&b| b as i32)
+            .ok_or_else(|| short(arr, pos + 1))
+    };
     let mut shl = 6;
-    let mut bit = arr[*pos] as i32;
+    let mut bit = byte(*pos)?;
     let mut res = bit & 0x3f;
     let sign = (bit & 0x40) == 0;
 
     while (bit & 0x80) != 0 {
         *pos += 1;
-        bit = arr[*pos] as i32;
-        res += (bit & 0x7f) << shl;
+        bit = byte(*pos)?;
+        res = res.wrapping_add((bit & 0x7f).wrapping_shl(shl));
         shl += 7;
     }
     *pos += 1;
 
-    if sign {
-        res
+    Ok(if sign { res } else { res.wrapping_neg() })
+}
+
+/// 把以 0.01 为单位解析的价格换算成以 `10^-decimal_point` 为单位的价格。
+///
+/// 实时行情、分时、逐笔成交的价格与 pytdx 相同，固定除以 100；
+/// 对于 decimal_point 为 3 的证券（ETF、债券等），价格会大 10 倍，需要使用此函数换算。
+/// decimal_point 见 [`SecurityListData`][crate::tcp::SecurityListData::decimal_point]。
+#[inline]
+pub fn rescale(price: f64, decimal_point: u8) -> f64 {
+    if decimal_point == 2 {
+        price
     } else {
-        -res
+        (price * 100.).round() / 10f64.powi(decimal_point as i32)
     }
 }
 
+/// 检查 arr 至少有 len 个字节，否则返回 [`Error::ShortFrame`]。
+///
+/// 解析定长的数据之前调用此函数，以免越界访问导致 panic 。
+#[inline]
+pub fn need(arr: &[u8], len: usize) -> Result<()> {
+    if arr.len() < len {
+        Err(short(arr, len))
+    } else {
+        Ok(())
+    }
+}
+
+/// 6 位股票代码的字节。字节长度不是 6 时返回 [`Error::InvalidCode`]。
+///
+/// 修改请求字节中的代码之前调用此函数，以免代码长度不正确导致 panic 。
+#[inline]
+pub fn code_bytes(code: &str) -> Result<[u8; 6]> {
+    code.as_bytes()
+        .try_into()
+        .map_err(|_| Error::InvalidCode(code.into()))
+}
+
+/// 把文件名写入请求字节中的定长字段，其余字节填 0。
+/// 字节长度超过字段长度时返回 [`Error::InvalidFilename`]，且不修改任何内容。
+pub fn filename_field(field: &mut [u8], filename: &str) -> Result<()> {
+    if filename.len() > field.len() {
+        return Err(Error::InvalidFilename {
+            name: filename.into(),
+            max: field.len(),
+        });
+    }
+    field.fill(0);
+    field[..filename.len()].copy_from_slice(filename.as_bytes());
+    Ok(())
+}
+
+fn short(arr: &[u8], expected: usize) -> Error {
+    Error::ShortFrame {
+        expected,
+        found: arr.len(),
+    }
+}
+
+/// 以 GBK 解码，并舍弃第一个 `\0` 及之后的字节。无效的字节见 [`gbk_lossy`][crate::bytes_helper::gbk_lossy]。
+pub fn gbk_until_nul(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    crate::bytes_helper::gbk_lossy(&bytes[..end])
+}
+
 pub fn vol_amount(ivol: i32) -> f64 {
     let logpoint = ivol >> 24;
     let hleax = (ivol >> 16) & 0xff;
@@ -144,12 +214,24 @@ fn check_datetime_price_vol_amount() {
     #[rustfmt::skip]
     assert_eq!(datetime(&[235, 100, 52, 1], 9),
                DateTime { year: 2021, month: 9, day: 23, hour: 15, minute: 0, });
-    assert_eq!(price(&[180, 154, 2], &mut 0), 18100);
-    assert_eq!(price(&[228, 6], &mut 0), -420);
-    assert_eq!(price(&[156, 3], &mut 0), 220);
-    assert_eq!(price(&[194, 7], &mut 0), -450);
+    assert_eq!(price(&[180, 154, 2], &mut 0).unwrap(), 18100);
+    assert_eq!(price(&[228, 6], &mut 0).unwrap(), -420);
+    assert_eq!(price(&[156, 3], &mut 0).unwrap(), 220);
+    assert_eq!(price(&[194, 7], &mut 0).unwrap(), -450);
     assert_eq!(vol_amount(1235775464), 1379837.0);
     assert_eq!(vol_amount(1326643033), 2465683712.0);
+    assert!(matches!(
+        price(&[180, 154], &mut 0),
+        Err(Error::ShortFrame {
+            expected: 3,
+            found: 2
+        })
+    ));
+    assert!(price(&[], &mut 0).is_err());
+    assert!(price(&[0xff; 8], &mut 0).is_err());
+    assert_eq!(rescale(17.68, 2), 17.68);
+    assert_eq!(rescale(48.72, 3), 4.872);
+    assert_eq!(rescale(100.01, 3), 10.001);
 
     // let arr = [235, 100, 52, 1, 180, 154, 2, 228, 6, 156, 3, 194, 7, 232, 111, 168, 73, 89,
     // 247, 18, 79];
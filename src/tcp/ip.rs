@@ -1,6 +1,6 @@
 //! 对应于 pytdx/util/best_ip.py 文件。
 //!
-//! 去除了失效的地址。暂时未验证 Stock 非 IP 和 Future 地址。
+//! 去除了失效的地址。暂时未验证 Stock 非 IP 地址和扩展行情地址（[`EX_IP`]）。
 //!
 //! Stock 非 IP 地址有：
 //!   {'ip': 'hq.cjis.cn', 'port': 7709},
@@ -36,6 +36,21 @@ lazy_static::lazy_static! {
         "61.152.249.56:7709".parse().unwrap(),
         // "61.153.209.138:7709".parse().unwrap() 失效
     ];
+
+    /// 扩展行情（期货、期权、港股等）的地址，见 [`exhq`][super::exhq]。对应于 pytdx 中的
+    /// future_ip ，只保留了 7727 端口的地址。
+    pub static ref EX_IP: [std::net::SocketAddr; 10] = [
+        "106.14.95.149:7727".parse().unwrap(),
+        "112.74.214.43:7727".parse().unwrap(),
+        "119.147.86.171:7727".parse().unwrap(),
+        "119.97.185.5:7727".parse().unwrap(),
+        "120.24.0.77:7727".parse().unwrap(),
+        "47.107.75.159:7727".parse().unwrap(),
+        "47.92.127.181:7727".parse().unwrap(),
+        "59.175.238.38:7727".parse().unwrap(),
+        "61.152.107.141:7727".parse().unwrap(),
+        "61.152.107.171:7727".parse().unwrap(),
+    ];
 }
 
 #[cfg(test)]
@@ -0,0 +1,182 @@
+//! 长连接的心跳保持。
+//!
+//! 服务器会断开长时间空闲的连接。[`KeepAlive`] 在后台线程中定期发送 [`Heartbeat`]，
+//! 并与正在进行的请求共用一把锁，保证同一个 [`Tcp`] 上的请求和心跳包不会交错。
+//!
+//! ```no_run
+//! use rustdx::tcp::{keepalive::KeepAlive, stock::Kline, Tcp};
+//! use std::time::Duration;
+//!
+//! let tcp = KeepAlive::new(Tcp::new()?, Duration::from_secs(10));
+//! let data = tcp.recv_parsed(&mut Kline::default())?.to_vec();
+//! assert!(tcp.is_alive());
+//! # Ok::<(), rustdx::Error>(())
+//! ```
+
+use super::{Heartbeat, Result, Tcp, Tdx};
+use std::{
+    io::{Error, ErrorKind},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex, MutexGuard,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+/// 带有心跳保持的 [`Tcp`] 连接。
+///
+/// - 当连接空闲超过 interval 时，后台线程发送一次心跳包；
+/// - 心跳包或者请求失败时，认为连接已经失效：后台线程退出，之后的请求直接返回
+///   [`NotConnected`][ErrorKind::NotConnected] 错误；
+/// - 丢弃时停止后台线程。
+#[derive(Debug)]
+pub struct KeepAlive {
+    shared: Arc<Shared>,
+    stop: Option<mpsc::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    alive: AtomicBool,
+}
+
+#[derive(Debug)]
+struct State {
+    tcp: Tcp,
+    /// 上一次发送请求或心跳包的时间。
+    last: Instant,
+}
+
+impl KeepAlive {
+    /// 接管 tcp ，并启动发送心跳包的后台线程。
+    pub fn new(tcp: Tcp, interval: Duration) -> Self {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                tcp,
+                last: Instant::now(),
+            }),
+            alive: AtomicBool::new(true),
+        });
+        let (stop, rx) = mpsc::channel();
+        let handle = {
+            let shared = shared.clone();
+            std::thread::spawn(move || heartbeat(&shared, interval, &rx))
+        };
+        Self {
+            shared,
+            stop: Some(stop),
+            handle: Some(handle),
+        }
+    }
+
+    /// 连接是否仍然可用。
+    pub fn is_alive(&self) -> bool {
+        self.shared.alive.load(Ordering::Acquire)
+    }
+
+    /// 在持有锁的情况下使用连接，期间后台线程不会发送心跳包。
+    ///
+    /// 当 f 返回错误时，认为连接已经失效。
+    pub fn with_tcp<T>(&self, f: impl FnOnce(&mut Tcp) -> Result<T>) -> Result<T> {
+        let mut state = self.lock()?;
+        let res = f(&mut state.tcp);
+        state.last = Instant::now();
+        if res.is_err() {
+            self.shared.alive.store(false, Ordering::Release);
+        }
+        res
+    }
+
+    /// 与 [`Tdx::recv_parsed`] 相同，只是在持有锁的情况下发送请求。
+    pub fn recv_parsed<'t, T: Tdx>(&self, tdx: &'t mut T) -> Result<&'t T::Item> {
+        let response = self.with_tcp(|tcp| tdx.recv(tcp))?;
+        tdx.parse(response)?;
+        Ok(tdx.result())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, State>> {
+        if !self.is_alive() {
+            return Err(Error::new(ErrorKind::NotConnected, "连接已失效").into());
+        }
+        self.shared
+            .state
+            .lock()
+            .map_err(|_| Error::other("连接的锁已中毒").into())
+    }
+}
+
+impl Drop for KeepAlive {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// 后台线程：每隔一段时间检查连接的空闲时间，空闲超过 interval 则发送心跳包。
+fn heartbeat(shared: &Shared, interval: Duration, stop: &mpsc::Receiver<()>) {
+    let mut wait = interval;
+    while let Err(mpsc::RecvTimeoutError::Timeout) = stop.recv_timeout(wait) {
+        let Ok(mut state) = shared.state.lock() else {
+            break;
+        };
+        let idle = state.last.elapsed();
+        if idle < interval {
+            wait = interval - idle;
+            continue;
+        }
+        if let Err(e) = Heartbeat::new(0).recv_parsed(&mut state.tcp) {
+            log::warn!("心跳包发送失败，连接已失效：{}", e);
+            shared.alive.store(false, Ordering::Release);
+            break;
+        }
+        state.last = Instant::now();
+        wait = interval;
+    }
+}
+
+#[test]
+fn keep_alive() -> Result<()> {
+    use super::tests::{count_server, COUNT};
+
+    // 轮询直到条件成立或者超过 2 秒，以免在繁忙的机器上因为线程调度而失败
+    let wait_until = |f: &dyn Fn() -> bool| {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while !f() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        f()
+    };
+
+    let server = count_server(Duration::ZERO)?;
+    let tcp = KeepAlive::new(Tcp::new_with_ip(&server.addr)?, Duration::from_millis(20));
+    let handshake = server.requests();
+    assert!(
+        wait_until(&|| server.requests() >= handshake + 3),
+        "{}",
+        server.requests()
+    );
+    assert!(tcp.is_alive());
+
+    // 请求与心跳包不会交错
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..20 {
+                    let count = *tcp.recv_parsed(&mut Heartbeat::new(1)).unwrap();
+                    assert_eq!(count, COUNT);
+                }
+            });
+        }
+    });
+
+    drop(server);
+    assert!(wait_until(&|| !tcp.is_alive()));
+    let err = tcp.recv_parsed(&mut Heartbeat::new(0)).unwrap_err();
+    assert!(matches!(err, crate::Error::Io(ref e) if e.kind() == ErrorKind::NotConnected));
+    Ok(())
+}
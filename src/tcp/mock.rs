@@ -0,0 +1,395 @@
+//! 本地模拟服务器，用于离线测试。需要开启 `mock` feature（crate 内部测试总是可用）。
+//!
+//! - [`MockServer`]：按照请求字节回放 [`Fixture`] 中记录的响应；
+//! - [`Recorder`]：在本地转发请求到真实服务器，并把每次请求和响应记录成 [`Fixture`]。
+//!
+//! 响应字节与真实服务器一致：16 字节的响应信息（见 [`ResponseHeader`][super::ResponseHeader]）
+//! 以及之后的 zlib 压缩数据，所以 [`send_recv_decompress`][super::send_recv_decompress]
+//! 不需要区分模拟服务器和真实服务器。
+//!
+//! ```no_run
+//! use rustdx::tcp::{mock::{Fixture, MockServer, Recorder}, stock::Kline, Tcp, Tdx};
+//!
+//! // 记录：通过 Recorder 连接真实服务器
+//! let recorder = Recorder::start(rustdx::tcp::ip::STOCK_IP[0])?;
+//! Kline::default().recv_parsed(&mut Tcp::new_with_ip(&recorder.addr)?)?;
+//! recorder.finish().save("kline.fixture")?;
+//!
+//! // 回放：不需要网络
+//! let server = MockServer::start(Fixture::load("kline.fixture")?)?;
+//! Kline::default().recv_parsed(&mut Tcp::new_with_ip(&server.addr)?)?;
+//! # Ok::<(), rustdx::Error>(())
+//! ```
+
+use super::{u16_from_le_bytes, Result, PACK1, PACK2, PACK3, RECV_SIZE};
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+/// 请求字节到完整响应字节（16 字节的响应信息和之后的数据）的映射。
+///
+/// 文件格式为文本：每行一对请求和响应，均为十六进制字符串，以空格分隔；`#` 开头的行为注释。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Fixture {
+    responses: BTreeMap<Vec<u8>, Vec<u8>>,
+    /// 没有记录的请求所使用的有效数据。
+    fallback: Option<Vec<u8>>,
+}
+
+impl Fixture {
+    /// 只包含三个测试包的响应（空数据），以便 [`Tcp::new_with_ip`][super::Tcp::new_with_ip] 能够连接。
+    pub fn handshake() -> Self {
+        let mut fixture = Self::default();
+        for pack in [PACK1, PACK2, PACK3] {
+            fixture.insert_data(pack, &[]);
+        }
+        fixture
+    }
+
+    /// 记录请求和完整的响应字节。
+    pub fn insert(&mut self, request: &[u8], response: Vec<u8>) -> &mut Self {
+        self.responses.insert(request.to_vec(), response);
+        self
+    }
+
+    /// 记录请求和解压后的有效数据，响应字节由 [`frame`] 生成。
+    pub fn insert_data(&mut self, request: &[u8], data: &[u8]) -> &mut Self {
+        self.insert(request, frame(request, data))
+    }
+
+    /// 对没有记录的请求，返回由 [`frame`] 生成的响应。
+    pub fn fallback_data(&mut self, data: &[u8]) -> &mut Self {
+        self.fallback = Some(data.to_vec());
+        self
+    }
+
+    /// 请求对应的完整响应字节。
+    pub fn get(&self, request: &[u8]) -> Option<Vec<u8>> {
+        match self.responses.get(request) {
+            Some(response) => Some(response.clone()),
+            None => Some(frame(request, self.fallback.as_deref()?)),
+        }
+    }
+
+    /// 记录的请求数量。
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// 合并另一个 Fixture 的记录：相同的请求以 other 为准。
+    pub fn merge(&mut self, other: Fixture) -> &mut Self {
+        self.responses.extend(other.responses);
+        self
+    }
+
+    /// 从文件读取。
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// 保存到文件。按请求字节排序，所以相同的记录总是得到相同的文件。
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        Ok(std::fs::write(path, self.to_string())?)
+    }
+}
+
+impl std::fmt::Display for Fixture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# rustdx mock fixture: <request hex> <response hex>")?;
+        for (request, response) in &self.responses {
+            writeln!(f, "{} {}", hex(request), hex(response))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Fixture {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut fixture = Self::default();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || crate::Error::Invalid {
+                expected: "<request hex> <response hex>".into(),
+                found: line.into(),
+            };
+            let (request, response) = line.split_once(' ').ok_or_else(invalid)?;
+            let request = unhex(request).ok_or_else(invalid)?;
+            let response = unhex(response.trim()).ok_or_else(invalid)?;
+            fixture.insert(&request, response);
+        }
+        Ok(fixture)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unhex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// 把有效数据封装成 request 的响应字节：16 字节的响应信息，之后为 zlib 压缩的数据。
+///
+/// 响应信息中的序号和消息类型取自 request（见 [`ResponseHeader`][super::ResponseHeader]）。
+/// 当压缩后的数据不比原数据短时，不压缩（解压前后的长度相等表示无需解压）。
+pub fn frame(request: &[u8], data: &[u8]) -> Vec<u8> {
+    let compressed = miniz_oxide::deflate::compress_to_vec_zlib(data, 6);
+    let body = if compressed.len() < data.len() {
+        &compressed[..]
+    } else {
+        data
+    };
+    let mut response = vec![0; RECV_SIZE];
+    response[..4].copy_from_slice(&super::ResponseHeader::MAGIC.to_le_bytes());
+    response[4] = 0x0c;
+    if let Some((seq, msg_type)) = super::ResponseHeader::request_id(request) {
+        response[5..9].copy_from_slice(&seq.to_le_bytes());
+        response[10..12].copy_from_slice(&msg_type.to_le_bytes());
+    }
+    response[12..14].copy_from_slice(&(body.len() as u16).to_le_bytes());
+    response[14..16].copy_from_slice(&(data.len() as u16).to_le_bytes());
+    response.extend_from_slice(body);
+    response
+}
+
+/// 读取一个完整的请求：10 字节的请求头，其中 6 ~ 7 字节为之后的字节长度。
+pub(crate) fn read_request(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut request = vec![0; 10];
+    stream.read_exact(&mut request)?;
+    let len = u16_from_le_bytes(&request, 6) as usize;
+    request.resize(10 + len, 0);
+    stream.read_exact(&mut request[10..])?;
+    Ok(request)
+}
+
+/// 读取一个完整的响应：16 字节的响应信息，其中 12 ~ 13 字节为之后的字节长度。
+fn read_response(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut response = vec![0; RECV_SIZE];
+    stream.read_exact(&mut response)?;
+    let len = u16_from_le_bytes(&response, 12) as usize;
+    response.resize(RECV_SIZE + len, 0);
+    stream.read_exact(&mut response[RECV_SIZE..])?;
+    Ok(response)
+}
+
+/// 每个连接各自的请求处理：返回 None 时关闭连接。
+type Handler = Box<dyn FnMut(&[u8]) -> Option<Vec<u8>> + Send>;
+
+/// 监听本地回环地址的服务器。丢弃时关闭监听和所有已建立的连接。
+#[derive(Debug)]
+struct Server {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    streams: Arc<Mutex<Vec<TcpStream>>>,
+    requests: Arc<AtomicUsize>,
+}
+
+impl Server {
+    fn start(
+        delay: Duration,
+        handler: impl Fn() -> std::io::Result<Handler> + Send + 'static,
+    ) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let server = Self {
+            addr: listener.local_addr()?,
+            stop: Default::default(),
+            streams: Default::default(),
+            requests: Default::default(),
+        };
+        let (stop, streams) = (server.stop.clone(), server.streams.clone());
+        let requests = server.requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(mut stream) = stream else { continue };
+                let Ok(mut handle) = handler() else {
+                    continue;
+                };
+                if let Ok(s) = stream.try_clone() {
+                    streams.lock().unwrap().push(s);
+                }
+                let requests = requests.clone();
+                std::thread::spawn(move || -> std::io::Result<()> {
+                    loop {
+                        let request = read_request(&mut stream)?;
+                        requests.fetch_add(1, Ordering::Relaxed);
+                        std::thread::sleep(delay);
+                        match handle(&request) {
+                            Some(response) => stream.write_all(&response)?,
+                            None => {
+                                log::warn!("没有记录的请求：{}", hex(&request));
+                                return stream.shutdown(Shutdown::Both);
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        Ok(server)
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // 唤醒阻塞在 accept 上的线程
+        let _ = TcpStream::connect(self.addr);
+        for s in self.streams.lock().unwrap().drain(..) {
+            let _ = s.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// 回放 [`Fixture`] 的模拟服务器，监听本地回环地址的随机端口。
+///
+/// 对于没有记录的请求（且没有设置 [`Fixture::fallback_data`]），直接关闭连接。
+/// 丢弃时关闭监听和所有已建立的连接。
+#[derive(Debug)]
+pub struct MockServer {
+    pub addr: SocketAddr,
+    server: Server,
+}
+
+impl MockServer {
+    pub fn start(fixture: Fixture) -> Result<Self> {
+        Self::start_delayed(fixture, Duration::ZERO)
+    }
+
+    /// 每次响应之前等待 delay 时间，用于模拟较慢的服务器。
+    pub fn start_delayed(fixture: Fixture, delay: Duration) -> Result<Self> {
+        let fixture = Arc::new(fixture);
+        let server = Server::start(delay, move || {
+            let fixture = fixture.clone();
+            Ok(Box::new(move |request: &[u8]| fixture.get(request)) as Handler)
+        })?;
+        Ok(Self {
+            addr: server.addr,
+            server,
+        })
+    }
+
+    /// 已收到的请求数量（包括三个测试包）。
+    pub fn requests(&self) -> usize {
+        self.server.requests.load(Ordering::Relaxed)
+    }
+
+    /// 一个没有监听的本地地址，连接时会被拒绝。
+    pub fn dead_addr() -> Result<SocketAddr> {
+        Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?)
+    }
+}
+
+/// 记录模式：在本地回环地址上监听，把每个请求转发到 upstream ，并记录请求和响应。
+///
+/// 每个连接到 Recorder 的 Tcp 会对应一个到 upstream 的连接。
+#[derive(Debug)]
+pub struct Recorder {
+    pub addr: SocketAddr,
+    fixture: Arc<Mutex<Fixture>>,
+    server: Server,
+}
+
+impl Recorder {
+    /// 转发时连接、读取和写入 upstream 的超时值。
+    pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn start(upstream: SocketAddr) -> Result<Self> {
+        let fixture = Arc::new(Mutex::new(Fixture::default()));
+        let recorded = fixture.clone();
+        let server = Server::start(Duration::ZERO, move || {
+            let mut upstream = TcpStream::connect_timeout(&upstream, Self::TIMEOUT)?;
+            upstream.set_read_timeout(Some(Self::TIMEOUT))?;
+            upstream.set_write_timeout(Some(Self::TIMEOUT))?;
+            let recorded = recorded.clone();
+            Ok(Box::new(move |request: &[u8]| {
+                upstream.write_all(request).ok()?;
+                let response = read_response(&mut upstream).ok()?;
+                recorded.lock().ok()?.insert(request, response.clone());
+                Some(response)
+            }) as Handler)
+        })?;
+        Ok(Self {
+            addr: server.addr,
+            fixture,
+            server,
+        })
+    }
+
+    /// 停止记录，返回已记录的 Fixture 。
+    pub fn finish(self) -> Fixture {
+        drop(self.server);
+        std::mem::take(&mut *self.fixture.lock().unwrap())
+    }
+}
+
+#[test]
+fn fixture_text() {
+    let mut fixture = Fixture::handshake();
+    fixture.insert(&[0x0c, 0x01], vec![0xb1, 0xcb, 0x74, 0x00]);
+    let text = fixture.to_string();
+    assert_eq!(text.parse::<Fixture>().unwrap(), fixture);
+    assert!("0c01 0".parse::<Fixture>().is_err());
+    assert!("0c01".parse::<Fixture>().is_err());
+    assert!("# comment\n\n".parse::<Fixture>().unwrap().is_empty());
+}
+
+#[test]
+fn replay_and_record() -> Result<()> {
+    use super::{stock::Kline, Tcp, Tdx};
+
+    // Kline 测试中的响应数据，压缩之后由模拟服务器返回
+    let data = [
+        0x03, 0x00, 0xeb, 0x64, 0x34, 0x01, 0xb4, 0x9a, 0x02, 0xe4, 0x06, 0x9c, 0x03, 0xc2, 0x07,
+        0xe8, 0x6f, 0xa8, 0x49, 0x59, 0xf7, 0x12, 0x4f, 0xec, 0x64, 0x34, 0x01, 0xd0, 0x01, 0xfa,
+        0x03, 0x90, 0x01, 0xc4, 0x04, 0x00, 0x81, 0x9a, 0x49, 0xb7, 0xb1, 0x03, 0x4f, 0xef, 0x64,
+        0x34, 0x01, 0xcc, 0x02, 0xa8, 0x05, 0x96, 0x07, 0xd6, 0x02, 0xd8, 0x3d, 0x8b, 0x49, 0x4b,
+        0xf0, 0xeb, 0x4e,
+    ];
+    let mut fixture = Fixture::handshake();
+    fixture.insert_data(Kline::SEND, &data);
+    let upstream = MockServer::start(fixture.clone())?;
+
+    let recorder = Recorder::start(upstream.addr)?;
+    let mut kline = Kline::default();
+    kline.recv_parsed(&mut Tcp::new_with_ip(&recorder.addr)?)?;
+    assert_eq!(kline.response, data);
+    let recorded = recorder.finish();
+    assert_eq!(recorded, fixture);
+
+    let server = MockServer::start(recorded.to_string().parse().unwrap())?;
+    let mut tcp = Tcp::new_with_ip(&server.addr)?;
+    let mut replayed = Kline::default();
+    replayed.recv_parsed(&mut tcp)?;
+    compare!(kline.data, replayed.data);
+
+    // 没有记录的请求会导致连接被关闭
+    assert!(Kline::new(1, "600000", 9, 0, 3)?
+        .recv_parsed(&mut tcp)
+        .is_err());
+    Ok(())
+}
@@ -1,7 +1,7 @@
-use crate::bytes_helper::u16_from_le_bytes;
+use crate::{bytes_helper::u16_from_le_bytes, Error, Result};
 use log::trace;
 use std::{
-    io::{BufReader, Read, Result, Write},
+    io::{BufReader, Read, Write},
     net::{SocketAddr, TcpStream},
     time::Duration,
 };
@@ -13,8 +13,19 @@ pub(crate) mod tests;
 mod basic;
 pub use basic::*;
 
+#[cfg(feature = "async")]
+pub mod async_tcp;
+pub mod batch;
+pub mod client;
+pub mod exhq;
+mod header;
+pub use header::ResponseHeader;
 pub mod helper;
 pub mod ip;
+pub mod keepalive;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod pool;
 
 pub mod stock;
 
@@ -27,40 +38,70 @@ pub struct Tcp {
     stream: TcpStream,
     buffer: BufTcp,
     recv: [u8; RECV_SIZE],
+    /// 建立连接时使用的协议，见 [`Protocol`]。
+    protocol: Protocol,
+    /// 是否检查响应的序号和消息类型，见 [`Tcp::check_header`]。
+    check_header: bool,
 }
 
 impl Tcp {
     /// 已发送三个测试包
     pub fn new() -> Result<Self> {
-        let (stream, buffer, recv) = tcpstream()?;
-        let mut tcp = Self {
-            stream,
-            buffer,
-            recv,
-        };
-        send_packs(&mut tcp, false)?;
-        Ok(tcp)
+        Self::new_with_ip(&ip::STOCK_IP[0])
     }
 
     /// 已发送三个测试包
     pub fn new_with_ip(ip: &SocketAddr) -> Result<Self> {
-        let (stream, buffer, recv) = tcpstream_ip(ip)?;
+        Self::new_with_ip_timeout(ip, TIMEOUT)
+    }
+
+    /// 已发送三个测试包。连接、读取和写入的超时值均为 timeout 。
+    pub fn new_with_ip_timeout(ip: &SocketAddr, timeout: Duration) -> Result<Self> {
+        Self::new_with_protocol(ip, timeout, Protocol::STOCK)
+    }
+
+    /// 按照 protocol 建立连接：已发送 [`Protocol::handshake`] 中的握手包。
+    /// 连接、读取和写入的超时值均为 timeout 。
+    ///
+    /// 之后只能发送 [`Tdx::PROTOCOL`] 与之相同的请求。
+    pub fn new_with_protocol(
+        ip: &SocketAddr,
+        timeout: Duration,
+        protocol: Protocol,
+    ) -> Result<Self> {
+        let (stream, buffer, recv) = tcpstream_ip_timeout(ip, timeout)?;
         let mut tcp = Self {
             stream,
             buffer,
             recv,
+            protocol,
+            check_header: protocol.check_header,
         };
-        send_packs(&mut tcp, false)?;
+        for (i, (pack, tag)) in protocol.handshake.iter().enumerate() {
+            let (header, _) = recv_frame(&mut tcp, pack, tag)?;
+            check_handshake(&mut tcp.check_header, i == 0, &header, pack)?;
+        }
         Ok(tcp)
     }
 
+    /// 建立连接时使用的协议。
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    /// 此连接是否检查响应的序号和消息类型：协议要求检查（[`Protocol::check_header`]），
+    /// 并且服务器对第一个握手包的响应原样返回了序号和消息类型（见 [`check_handshake`]）。
+    pub fn check_header(&self) -> bool {
+        self.check_header
+    }
+
     /// 发送并接收字节。需要对接收的字节进行解析（参考 [`Tdx::parse`] 的实现）。
     ///
     /// 方法返回发送和读取的字节数。
     ///
     /// 注意是读取而不是接收的字节数。
     /// 由于每次接收先读取 16 字节，所以返回的元组中，第二个数字应为 16。
-    pub fn send_recv(&mut self, send: &[u8]) -> Result<(usize, usize)> {
+    pub fn send_recv(&mut self, send: &[u8]) -> std::io::Result<(usize, usize)> {
         Ok((self.stream.write(send)?, self.buffer.read(&mut self.recv)?))
     }
 
@@ -75,6 +116,53 @@ impl Tcp {
     pub fn get_ref_recv(&self) -> &[u8] {
         &self.recv
     }
+
+    /// 最近一次接收的响应信息。用于诊断错位或者迟到的响应。
+    pub fn header(&self) -> ResponseHeader {
+        ResponseHeader::new(&self.recv)
+    }
+}
+
+/// 请求所属的行情协议：决定建立连接时发送的握手包，以及是否检查响应的序号和消息类型。
+///
+/// 每个 [`Tdx`] 类型通过 [`Tdx::PROTOCOL`] 声明所属的协议。同步的 [`Tcp`] 和异步的
+/// `AsyncTcp` 都按照协议握手和检查响应，并且拒绝其他协议的请求。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Protocol {
+    /// 协议的名称，用于错误信息。
+    pub name: &'static str,
+    /// 建立连接之后依次发送的握手包，以及描述它们的 tag 。
+    pub handshake: &'static [(&'static [u8], &'static str)],
+    /// 是否使用 [`ResponseHeader::check`] 检查响应的序号和消息类型。
+    pub check_header: bool,
+}
+
+impl Protocol {
+    /// 标准行情（端口通常为 7709）：握手包为 [`PACK1`]、[`PACK2`]、[`PACK3`]。
+    pub const STOCK: Self = Self {
+        name: "stock",
+        handshake: &[(PACK1, "PACK1"), (PACK2, "PACK2"), (PACK3, "PACK3")],
+        check_header: true,
+    };
+
+    /// 扩展行情（端口通常为 7727）：握手包为 [`EX_SETUP`][exhq::EX_SETUP]。
+    pub const EXHQ: Self = Self {
+        name: "exhq",
+        handshake: &[(exhq::EX_SETUP, "EX_SETUP")],
+        check_header: true,
+    };
+
+    /// 检查请求 T 的协议与此协议（即连接的协议）一致，否则返回 [`Error::Invalid`]。
+    pub fn check<T: Tdx + ?Sized>(self) -> Result<()> {
+        if T::PROTOCOL == self {
+            Ok(())
+        } else {
+            Err(Error::Invalid {
+                expected: format!("{} 协议的连接（{}）", T::PROTOCOL.name, T::TAG),
+                found: self.name.into(),
+            })
+        }
+    }
 }
 
 pub trait Tdx {
@@ -96,6 +184,11 @@ pub trait Tdx {
     const TAG: &'static str;
     /// 发送的字节的长度。每种请求所发送的字节长度是已知的。默认为 [`Tdx::SEND`] 的长度。
     const LEN: usize = Self::SEND.len();
+    /// 请求所属的协议，决定连接的握手包以及是否检查响应信息。默认为 [`Protocol::STOCK`]。
+    ///
+    /// 同步和异步的连接都按照此常量选择传输方式；`AsyncTcp` 不会调用 [`Tdx::recv`]，
+    /// 所以不要通过重写 [`Tdx::recv`] 改变传输方式。
+    const PROTOCOL: Protocol = Protocol::STOCK;
     type Item: ?Sized;
 
     /// 真正发送的字节。
@@ -107,12 +200,18 @@ pub trait Tdx {
     }
 
     /// 解析响应的字节。
-    fn parse(&mut self, response: Vec<u8>);
+    ///
+    /// 响应的字节不完整时返回 [`Error::ShortFrame`]，而不是 panic ；
+    /// 数量与预期不一致时返回 [`Error::CountMismatch`]。
+    fn parse(&mut self, response: Vec<u8>) -> Result<()>;
 
     /// 得到和解析响应的字节，并返回解析的数据。
+    ///
+    /// 连接的协议与 [`Tdx::PROTOCOL`] 不一致时返回 [`Error::Invalid`]，不发送请求。
     fn recv_parsed(&mut self, tcp: &mut Tcp) -> Result<&Self::Item> {
+        tcp.protocol.check::<Self>()?;
         let response = self.recv(tcp)?;
-        self.parse(response);
+        self.parse(response)?;
         Ok(self.result())
     }
 
@@ -127,16 +226,28 @@ pub trait Tdx {
 ///
 /// 有效数据：包含实际有用信息的数据。
 pub fn send_recv_decompress(tcp: &mut Tcp, send: &[u8], tag: &str) -> Result<Vec<u8>> {
-    let (mut buf, deflate_size, inflate_size) = send_recv(tcp, send, tag)?;
+    let (buf, deflate_size, inflate_size) = send_recv(tcp, send, tag)?;
+    inflate(buf, deflate_size, inflate_size)
+}
 
+/// 根据响应信息的解压前后长度，进行数据解压。同步和异步方式共用此函数。
+///
+/// 解压失败或者解压后的长度与响应信息不一致时，返回 [`Error::Decompress`]。
+pub fn inflate(mut buf: Vec<u8>, deflate_size: u16, inflate_size: u16) -> Result<Vec<u8>> {
     if deflate_size != inflate_size {
-        buf = miniz_oxide::inflate::decompress_to_vec_zlib(&buf).unwrap();
+        buf = miniz_oxide::inflate::decompress_to_vec_zlib(&buf)
+            .map_err(|e| Error::Decompress(e.to_string()))?;
         trace!("解压后数据：\n{:?}\n", buf);
-        debug_assert_eq!(buf.len(), inflate_size as usize);
+        if buf.len() != inflate_size as usize {
+            return Err(Error::Decompress(format!(
+                "expected {} bytes after decompression, found {}",
+                inflate_size,
+                buf.len()
+            )));
+        }
     } else {
         trace!("无需解压\n");
     };
-
     Ok(buf)
 }
 
@@ -145,37 +256,105 @@ pub fn send_recv_decompress(tcp: &mut Tcp, send: &[u8], tag: &str) -> Result<Vec
 // 否则下次 read 的内容是上次的未读字节。
 // 如果不使用 BufReader ，那么需要手动 read 剩余字节。
 // 对于 TcpStream ，Write::flush 没有做任何事情，所以无需调用。
+//
+// 响应信息不足 16 字节时，继续读取剩余部分；读取失败或者魔数不正确则返回 `Error::BadHeader`。
+// 读取有效数据之后，如果连接要求，检查响应的序号和消息类型（见 `Tcp::check_header`）。
 pub fn send_recv(tcp: &mut Tcp, send: &[u8], tag: &str) -> Result<(Vec<u8>, u16, u16)> {
-    tcp.send_recv(send)?;
+    let (header, buf) = recv_frame(tcp, send, tag)?;
+    if tcp.check_header {
+        header.check(send)?;
+    }
+    Ok((buf, header.deflate_size, header.inflate_size))
+}
+
+/// 握手时检查响应信息。
+///
+/// 新建立的连接上不会有迟到的响应，所以第一个握手包（first 为 true）的响应与请求不一致时，
+/// 说明服务器不会原样返回序号和消息类型：记录一条警告，并把 check_header 设为 false ，
+/// 此连接之后不再检查，而不是让每个请求都失败。其余握手包按照 check_header 检查。
+pub fn check_handshake(
+    check_header: &mut bool,
+    first: bool,
+    header: &ResponseHeader,
+    pack: &[u8],
+) -> Result<()> {
+    if !*check_header {
+        return Ok(());
+    }
+    match header.check(pack) {
+        Err(e) if first => {
+            log::warn!("服务器没有原样返回请求的序号和消息类型，此连接不再检查响应信息：{e}");
+            *check_header = false;
+            Ok(())
+        }
+        res => res,
+    }
+}
+
+/// 发送字节，读取响应信息和有效数据（未解压），不检查序号和消息类型。
+fn recv_frame(tcp: &mut Tcp, send: &[u8], tag: &str) -> Result<(ResponseHeader, Vec<u8>)> {
+    let (_, n) = tcp.send_recv(send)?;
+    if n == 0 {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    } else if n < RECV_SIZE && tcp.buffer.read_exact(&mut tcp.recv[n..]).is_err() {
+        return Err(Error::BadHeader(tcp.recv[..n].to_vec()));
+    }
     trace!("{}\nsend: {:?}\nrecv[16B]: {:?}", tag, send, tcp);
+    let header = ResponseHeader::parse(&tcp.recv)?;
 
-    let deflate_size = u16_from_le_bytes(&tcp.recv, 12); // 响应信息中的待解压长度
+    let deflate_size = header.deflate_size; // 响应信息中的待解压长度
     let mut buf = vec![0; deflate_size as usize];
     tcp.buffer.read_exact(&mut buf)?;
 
-    let inflate_size = u16_from_le_bytes(&tcp.recv, 14); // 响应信息中的解压后长度
+    let inflate_size = header.inflate_size; // 响应信息中的解压后长度
     #[rustfmt::skip]
     trace!("\n解压前：#{:?}# -> {}，解压后：#{:?}# -> {}\n剩余数据（即解压前）：{:x?}\n",
            &tcp.recv[12..14], deflate_size, &tcp.recv[14..16], inflate_size, buf);
 
-    Ok((buf, deflate_size, inflate_size))
+    Ok((header, buf))
 }
 
 /// 默认的超时值。
 pub const TIMEOUT: Duration = Duration::from_millis(100);
 
 /// 快速引入 tcpstream，设置 100 毫秒超时。
-/// TODO: 固定？随机？取最快的 ip？
-pub fn tcpstream() -> Result<(TcpStream, BufTcp, [u8; RECV_SIZE])> {
+///
+/// 固定连接 [`STOCK_IP`][ip::STOCK_IP] 的第一个地址。如需选取最快的地址，使用 [`pool::Pool`]。
+pub fn tcpstream() -> std::io::Result<(TcpStream, BufTcp, [u8; RECV_SIZE])> {
     tcpstream_ip(&ip::STOCK_IP[0])
 }
 
 /// 快速引入 tcpstream，设置 100 毫秒超时。
-pub fn tcpstream_ip(ip: &SocketAddr) -> Result<(TcpStream, BufTcp, [u8; RECV_SIZE])> {
-    let stream = TcpStream::connect_timeout(ip, TIMEOUT)?;
-    stream.set_read_timeout(Some(TIMEOUT))?;
-    stream.set_write_timeout(Some(TIMEOUT))?;
+pub fn tcpstream_ip(ip: &SocketAddr) -> std::io::Result<(TcpStream, BufTcp, [u8; RECV_SIZE])> {
+    tcpstream_ip_timeout(ip, TIMEOUT)
+}
+
+/// 引入 tcpstream，连接、读取和写入的超时值均为 timeout 。
+pub fn tcpstream_ip_timeout(
+    ip: &SocketAddr,
+    timeout: Duration,
+) -> std::io::Result<(TcpStream, BufTcp, [u8; RECV_SIZE])> {
+    let stream = TcpStream::connect_timeout(ip, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
     let recv = [0; RECV_SIZE];
     let buffer = BufReader::new(stream.try_clone()?);
     Ok((stream, buffer, recv))
 }
+
+#[test]
+fn inflate_error() {
+    let data = [1, 2, 3, 4];
+    let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&data, 6);
+    let len = compressed.len() as u16;
+    assert_eq!(inflate(compressed.clone(), len, 4).unwrap(), data);
+    assert!(matches!(
+        inflate(compressed, len, 5),
+        Err(Error::Decompress(_))
+    ));
+    assert!(matches!(
+        inflate(vec![0xff; 4], 4, 8),
+        Err(Error::Decompress(_))
+    ));
+    assert_eq!(inflate(data.to_vec(), 4, 4).unwrap(), data);
+}
@@ -0,0 +1,220 @@
+//! 连接池：从多个服务器地址中选取握手最快的地址，并复用已建立的 [`Tcp`] 连接。
+//!
+//! ```no_run
+//! use rustdx::tcp::{pool::Pool, stock::Kline, Tdx};
+//!
+//! let mut pool = Pool::with_stock_ip(2)?;
+//! let mut tcp = pool.get()?;
+//! Kline::default().recv_parsed(&mut tcp)?;
+//! pool.put(tcp);
+//! # Ok::<(), rustdx::Error>(())
+//! ```
+
+use super::{Heartbeat, Result, Tcp, Tdx, TIMEOUT};
+use std::{
+    io::{Error, ErrorKind},
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// 连接一个地址并发送三个测试包（见 [`send_packs`][super::send_packs]），返回连接和握手耗时。
+///
+/// timeout 为连接以及读写的超时时间。
+pub fn probe(addr: &SocketAddr, timeout: Duration) -> Result<(Tcp, Duration)> {
+    let now = Instant::now();
+    let tcp = Tcp::new_with_ip_timeout(addr, timeout)?;
+    Ok((tcp, now.elapsed()))
+}
+
+/// 并发地探测所有地址，按握手耗时从小到大排序。无法连接的地址不在结果中。
+pub fn rank(addrs: &[SocketAddr], timeout: Duration) -> Vec<(SocketAddr, Tcp, Duration)> {
+    let mut ranked: Vec<_> = std::thread::scope(|s| {
+        let handles: Vec<_> = addrs
+            .iter()
+            .map(|addr| s.spawn(move || probe(addr, timeout).map(|(tcp, t)| (*addr, tcp, t))))
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|h| h.join().ok()?.ok())
+            .collect()
+    });
+    ranked.sort_by_key(|(_, _, t)| *t);
+    ranked
+}
+
+/// [`Tcp`] 连接池。
+///
+/// - 创建时使用 [`rank`] 并发探测地址，保留最快的若干个连接；
+/// - [`Pool::get`] 取出空闲连接前先发送心跳包检查，失效的连接会被丢弃；
+///   没有空闲连接时，按握手耗时依次连接地址，连接失败的地址会被移到末尾，
+///   之后仍会重试（服务器可能只是暂时不可用）；
+/// - 使用完毕后，通过 [`Pool::put`] 归还连接。出错的连接直接丢弃即可，无需归还。
+#[derive(Debug)]
+pub struct Pool {
+    /// 可用的地址及其握手耗时，从快到慢排列；连接失败的地址排在末尾。
+    addrs: Vec<(SocketAddr, Duration)>,
+    /// 空闲的连接。
+    idle: Vec<Tcp>,
+    /// 空闲连接数量的上限。
+    size: usize,
+    /// 连接以及读写的超时时间。
+    timeout: Duration,
+}
+
+impl Pool {
+    /// 探测 addrs 中的所有地址，保留最快的 size 个连接作为空闲连接。
+    ///
+    /// 当所有地址都无法连接时，返回 [`NotConnected`][ErrorKind::NotConnected] 错误。
+    pub fn new(addrs: &[SocketAddr], size: usize) -> Result<Self> {
+        Self::new_with_timeout(addrs, size, TIMEOUT)
+    }
+
+    /// 与 [`Pool::new`] 相同，但使用 timeout 作为连接以及读写的超时时间（默认为
+    /// [`TIMEOUT`]）。之后 [`Pool::get`] 新建的连接也使用此超时时间。
+    pub fn new_with_timeout(addrs: &[SocketAddr], size: usize, timeout: Duration) -> Result<Self> {
+        let mut addrs_ranked = Vec::with_capacity(addrs.len());
+        let mut idle = Vec::with_capacity(size);
+        for (addr, tcp, t) in rank(addrs, timeout) {
+            addrs_ranked.push((addr, t));
+            if idle.len() < size {
+                idle.push(tcp);
+            }
+        }
+        if addrs_ranked.is_empty() {
+            return Err(not_connected());
+        }
+        // 先取出的是 Vec 末尾的连接，所以让最快的连接在最后
+        idle.reverse();
+        Ok(Self {
+            addrs: addrs_ranked,
+            idle,
+            size,
+            timeout,
+        })
+    }
+
+    /// 使用 [`STOCK_IP`][super::ip::STOCK_IP] 中的地址创建连接池。
+    pub fn with_stock_ip(size: usize) -> Result<Self> {
+        Self::new(&super::ip::STOCK_IP[..], size)
+    }
+
+    /// 地址及其握手耗时，从快到慢排列；连接失败的地址排在末尾。
+    pub fn addrs(&self) -> &[(SocketAddr, Duration)] {
+        &self.addrs
+    }
+
+    /// 最快的地址。
+    pub fn best(&self) -> Option<SocketAddr> {
+        self.addrs.first().map(|(addr, _)| *addr)
+    }
+
+    /// 空闲连接的数量。
+    pub fn idle(&self) -> usize {
+        self.idle.len()
+    }
+
+    /// 取出一个可用的连接。
+    ///
+    /// 没有空闲连接时，依次尝试每个地址一次；连接失败的地址移到末尾，而不是移除。
+    /// 当所有地址都无法连接时，返回 [`NotConnected`][ErrorKind::NotConnected] 错误。
+    pub fn get(&mut self) -> Result<Tcp> {
+        while let Some(mut tcp) = self.idle.pop() {
+            if alive(&mut tcp) {
+                return Ok(tcp);
+            }
+        }
+        for _ in 0..self.addrs.len() {
+            let addr = self.addrs[0].0;
+            match Tcp::new_with_ip_timeout(&addr, self.timeout) {
+                Ok(tcp) => return Ok(tcp),
+                Err(e) => {
+                    log::warn!("无法连接地址 {}，稍后重试：{}", addr, e);
+                    self.addrs.rotate_left(1);
+                }
+            }
+        }
+        Err(not_connected())
+    }
+
+    /// 归还连接。当空闲连接已满时，直接丢弃此连接。
+    pub fn put(&mut self, tcp: Tcp) {
+        if self.idle.len() < self.size {
+            self.idle.push(tcp);
+        }
+    }
+
+    /// 对所有空闲连接发送心跳包，丢弃失效的连接，返回剩余的空闲连接数量。
+    pub fn check(&mut self) -> usize {
+        self.idle.retain_mut(alive);
+        self.idle.len()
+    }
+}
+
+/// 发送心跳包检查连接是否可用。
+fn alive(tcp: &mut Tcp) -> bool {
+    Heartbeat::new(0).recv_parsed(tcp).is_ok()
+}
+
+/// 所有地址都无法连接。
+fn not_connected() -> crate::Error {
+    Error::new(ErrorKind::NotConnected, "没有可以连接的地址").into()
+}
+
+#[test]
+fn rank_and_demote() -> Result<()> {
+    use super::tests::{count_server, MockServer, COUNT};
+
+    let slow = count_server(Duration::from_millis(20))?;
+    let fast = count_server(Duration::ZERO)?;
+    let dead = MockServer::dead_addr()?;
+    let (slow_addr, fast_addr) = (slow.addr, fast.addr);
+
+    let ranked = rank(&[slow.addr, dead, fast.addr], TIMEOUT);
+    let addrs: Vec<_> = ranked.iter().map(|(addr, _, _)| *addr).collect();
+    assert_eq!(addrs, [fast.addr, slow.addr]);
+
+    let mut pool = Pool::new(&[dead, slow.addr, fast.addr], 1)?;
+    assert_eq!(pool.best(), Some(fast.addr));
+    assert_eq!(pool.idle(), 1);
+    assert_eq!(pool.check(), 1);
+
+    let mut tcp = pool.get()?;
+    assert_eq!(*Heartbeat::new(0).recv_parsed(&mut tcp)?, COUNT);
+    // 没有空闲连接时会新建连接
+    let tcp2 = pool.get()?;
+    pool.put(tcp);
+    pool.put(tcp2);
+    assert_eq!(pool.idle(), 1);
+
+    // 服务器关闭之后，空闲连接失效，地址被移到末尾
+    drop(fast);
+    assert_eq!(pool.check(), 0);
+    pool.get()?;
+    assert_eq!(pool.best(), Some(slow.addr));
+    drop(slow);
+    let err = pool.get().unwrap_err();
+    assert!(matches!(err, crate::Error::Io(ref e) if e.kind() == ErrorKind::NotConnected));
+    // 地址不会被移除：服务器恢复之后仍可连接
+    let addrs: Vec<_> = pool.addrs().iter().map(|(addr, _)| *addr).collect();
+    assert_eq!(addrs, [slow_addr, fast_addr]);
+    Ok(())
+}
+
+#[test]
+fn timeout() -> Result<()> {
+    let slow = super::tests::count_server(Duration::from_millis(50))?;
+    let err = Pool::new_with_timeout(&[slow.addr], 1, Duration::from_millis(10)).unwrap_err();
+    assert!(matches!(err, crate::Error::Io(ref e) if e.kind() == ErrorKind::NotConnected));
+
+    let mut pool = Pool::new_with_timeout(&[slow.addr], 0, Duration::from_secs(1))?;
+    assert_eq!(pool.idle(), 0);
+    pool.get()?;
+    Ok(())
+}
+
+#[test]
+fn no_reachable_addr() -> Result<()> {
+    let err = Pool::new(&[super::tests::MockServer::dead_addr()?], 1).unwrap_err();
+    assert!(matches!(err, crate::Error::Io(ref e) if e.kind() == ErrorKind::NotConnected));
+    Ok(())
+}
@@ -0,0 +1,329 @@
+use crate::{
+    file::block::BlockData,
+    tcp::{helper::filename_field, Tcp, Tdx},
+};
+
+/// 查询板块文件的信息（大小和哈希值）。对应于 pytdx 中的 hq.get_block_info_meta、
+/// GetBlockInfoMeta。
+///
+/// 常用的板块文件有：`block_zs.dat`（指数板块）、`block_gn.dat`（概念板块）、
+/// `block_fg.dat`（风格板块）和 `block.dat`（一般板块）。
+///
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct BlockInfoMeta<'d> {
+    pub send: Box<[u8]>,
+    pub filename: &'d str,
+    pub response: Vec<u8>,
+    pub data: BlockInfoMetaData,
+}
+
+impl<'d> Default for BlockInfoMeta<'d> {
+    fn default() -> Self {
+        Self {
+            send: {
+                let mut arr = [0; Self::LEN];
+                arr.copy_from_slice(Self::SEND);
+                arr.into()
+            },
+            filename: "block_zs.dat",
+            response: Vec::new(),
+            data: BlockInfoMetaData::default(),
+        }
+    }
+}
+
+impl<'d> BlockInfoMeta<'d> {
+    /// 当 filename 的字节长度超过 40 时，返回 [`Error::InvalidFilename`][crate::Error::InvalidFilename]。
+    pub fn new(filename: &'d str) -> crate::Result<Self> {
+        let mut meta = Self::default();
+        meta.filename(filename)?;
+        Ok(meta)
+    }
+
+    /// 修改板块文件名。
+    ///
+    /// 当 filename 的字节长度超过 40 时，返回 [`Error::InvalidFilename`][crate::Error::InvalidFilename]，
+    /// 且不修改任何内容。
+    pub fn filename(&mut self, filename: &'d str) -> crate::Result<&mut Self> {
+        filename_field(&mut self.send[12..52], filename)?;
+        self.filename = filename;
+        Ok(self)
+    }
+}
+
+impl<'d> Tdx for BlockInfoMeta<'d> {
+    type Item = BlockInfoMetaData;
+
+    /// `block_zs.dat` 的文件信息的请求字节。长度为 52。
+    /// ```python
+    /// struct.pack("<40s", bytes) # 后 40 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x0c, 0x39, 0x18, 0x69, 0x00, 0x01, 0x2a, 0x00, 0x2a, 0x00, 0xc5, 0x02, 0x62, 0x6c, 0x6f,
+        0x63, 0x6b, 0x5f, 0x7a, 0x73, 0x2e, 0x64, 0x61, 0x74, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    const TAG: &'static str = "板块文件信息";
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// ```python
+    /// (size, _, hash_value, _) = struct.unpack("<I1s32s1s", bytes) # python 表示方式
+    /// ```
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        crate::tcp::helper::need(&v, 37)?;
+        self.data = BlockInfoMetaData {
+            size: crate::bytes_helper::u32_from_le_bytes(&v, 0),
+            hash: String::from_utf8_lossy(&v[5..37]).into_owned(),
+        };
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// [`BlockInfoMeta`] 的解析结果。
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct BlockInfoMetaData {
+    /// 板块文件的字节长度
+    pub size: u32,
+    /// 板块文件的哈希值（32 位十六进制字符串）
+    pub hash: String,
+}
+
+/// 分块下载板块文件。对应于 pytdx 中的 hq.get_block_info、GetBlockInfo。
+///
+/// 一次请求只返回文件的一部分，通常使用 [`BlockInfo::recv_file`] 或
+/// [`BlockInfo::recv_blocks`] 一次性下载整个文件。
+///
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct BlockInfo<'d> {
+    pub send: Box<[u8]>,
+    pub filename: &'d str,
+    pub start: u32,
+    pub size: u32,
+    pub response: Vec<u8>,
+    /// 此次下载的文件片段
+    pub data: Vec<u8>,
+}
+
+/// 为了对应 [`BlockInfo::SEND`] 的含义，以下默认值值得注意：
+/// 1. filename 默认为 `block_zs.dat`；
+/// 2. start 默认为 0；
+/// 3. size 默认为 [`BlockInfo::CHUNK_SIZE`]。
+impl<'d> Default for BlockInfo<'d> {
+    fn default() -> Self {
+        Self {
+            send: {
+                let mut arr = [0; Self::LEN];
+                arr.copy_from_slice(Self::SEND);
+                arr.into()
+            },
+            filename: "block_zs.dat",
+            start: 0,
+            size: Self::CHUNK_SIZE,
+            response: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+}
+
+impl<'d> BlockInfo<'d> {
+    /// 每次下载的最大字节数。
+    pub const CHUNK_SIZE: u32 = 0x7530;
+
+    /// 当 filename 的字节长度超过 100 时，返回 [`Error::InvalidFilename`][crate::Error::InvalidFilename]。
+    pub fn new(filename: &'d str, start: u32, size: u32) -> crate::Result<Self> {
+        let mut block = Self::default();
+        block.filename(filename)?.start(start).size(size);
+        Ok(block)
+    }
+
+    /// 修改板块文件名。
+    ///
+    /// 当 filename 的字节长度超过 100 时，返回 [`Error::InvalidFilename`][crate::Error::InvalidFilename]，
+    /// 且不修改任何内容。
+    pub fn filename(&mut self, filename: &'d str) -> crate::Result<&mut Self> {
+        filename_field(&mut self.send[20..120], filename)?;
+        self.filename = filename;
+        Ok(self)
+    }
+
+    /// 修改起始位置。
+    pub fn start(&mut self, start: u32) -> &mut Self {
+        self.start = start;
+        self.send[12..16].copy_from_slice(&start.to_le_bytes());
+        self
+    }
+
+    /// 修改下载的字节数。
+    pub fn size(&mut self, size: u32) -> &mut Self {
+        self.size = size;
+        self.send[16..20].copy_from_slice(&size.to_le_bytes());
+        self
+    }
+
+    /// 先使用 [`BlockInfoMeta`] 查询文件大小，再以 [`BlockInfo::CHUNK_SIZE`]
+    /// 为单位分块下载，返回拼接后的整个文件。
+    ///
+    /// 下载的字节数与文件大小不一致时，返回 [`Error::CountMismatch`][crate::Error::CountMismatch]。
+    pub fn recv_file(filename: &'d str, tcp: &mut Tcp) -> crate::Result<Vec<u8>> {
+        let size = BlockInfoMeta::new(filename)?.recv_parsed(tcp)?.size;
+        let mut file = Vec::with_capacity(size as usize);
+        let mut block = Self::new(filename, 0, Self::CHUNK_SIZE)?;
+        for start in (0..size).step_by(Self::CHUNK_SIZE as usize) {
+            block.start(start).size(Self::CHUNK_SIZE.min(size - start));
+            file.extend_from_slice(block.recv_parsed(tcp)?);
+        }
+        if file.len() != size as usize {
+            return Err(crate::Error::CountMismatch {
+                expected: size as usize,
+                found: file.len(),
+            });
+        }
+        Ok(file)
+    }
+
+    /// 下载整个板块文件，并使用 [`BlockData::parse`] 解析。
+    pub fn recv_blocks(filename: &'d str, tcp: &mut Tcp) -> crate::Result<Vec<BlockData>> {
+        Ok(BlockData::parse(&Self::recv_file(filename, tcp)?))
+    }
+}
+
+impl<'d> Tdx for BlockInfo<'d> {
+    type Item = [u8];
+
+    /// `block_zs.dat` 从 0 开始 30000 字节的请求字节。长度为 120。
+    /// ```python
+    /// struct.pack("<II100s", bytes) # 后 108 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x0c, 0x37, 0x18, 0x6a, 0x00, 0x01, 0x6e, 0x00, 0x6e, 0x00, 0xb9, 0x06, 0x00, 0x00, 0x00,
+        0x00, 0x30, 0x75, 0x00, 0x00, 0x62, 0x6c, 0x6f, 0x63, 0x6b, 0x5f, 0x7a, 0x73, 0x2e, 0x64,
+        0x61, 0x74, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    const TAG: &'static str = "板块文件";
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 4 字节未知，剩余字节为文件片段。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        crate::tcp::helper::need(&v, 4)?;
+        self.data.clear();
+        self.data.extend_from_slice(&v[4..]);
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+#[test]
+fn block_new_modify() -> crate::Result<()> {
+    assert_eq!(BlockInfoMeta::LEN, 52);
+    assert_eq!(BlockInfo::LEN, 120);
+
+    let meta = BlockInfoMeta::new("block_zs.dat")?;
+    let mut meta2 = BlockInfoMeta::new("block_gn_long_name.dat")?;
+    meta2.filename("block_zs.dat")?;
+    compare!(BlockInfoMeta::default(), meta, meta2);
+
+    let block = BlockInfo::new("block_zs.dat", 0, BlockInfo::CHUNK_SIZE)?;
+    let mut block2 = BlockInfo::new("block_gn_long_name.dat", 30000, 100)?;
+    block2
+        .filename("block_zs.dat")?
+        .start(0)
+        .size(BlockInfo::CHUNK_SIZE);
+    compare!(BlockInfo::default(), block, block2);
+
+    let long = "b".repeat(41);
+    assert!(BlockInfoMeta::new(&long).is_err());
+    assert!(meta2.filename(&long).is_err());
+    compare!(BlockInfoMeta::default(), meta2);
+    let long = "b".repeat(101);
+    assert!(matches!(
+        BlockInfo::new(&long, 0, 1),
+        Err(crate::Error::InvalidFilename { max: 100, .. })
+    ));
+    assert!(BlockInfo::new(&long[..100], 0, 1).is_ok());
+    Ok(())
+}
+
+#[test]
+fn connection() -> crate::Result<()> {
+    crate::tcp::tests::connection(BlockInfoMeta::default())?;
+    let blocks = crate::tcp::tests::with_tcp(|tcp| BlockInfo::recv_blocks("block_zs.dat", tcp))?;
+    assert!(!blocks.is_empty());
+    Ok(())
+}
+
+#[test]
+fn parse() {
+    let mut meta = BlockInfoMeta::default();
+    let mut arr = vec![0x30, 0x75, 0x00, 0x00, 0x00];
+    arr.extend_from_slice(b"0123456789abcdef0123456789abcdef");
+    arr.push(0x00);
+    crate::tcp::tests::truncated(&mut meta, &arr[..37]);
+    meta.parse(arr).unwrap();
+    assert_eq!(meta.data.size, 30000);
+    assert_eq!(meta.data.hash, "0123456789abcdef0123456789abcdef");
+
+    // 一个板块文件：文件头 + 板块数量 + 两个板块
+    let mut file = vec![0; BlockData::HEADER_LEN];
+    file.extend_from_slice(&[0x02, 0x00]);
+    for (name, block_type, codes) in [
+        ("沪深300", 2u16, &["000001", "600000"][..]),
+        ("军工", 4, &["600760"]),
+    ] {
+        let mut chunk = [0; BlockData::LEN];
+        let name = encoding_rs::GBK.encode(name).0;
+        chunk[..name.len()].copy_from_slice(&name);
+        chunk[9..11].copy_from_slice(&(codes.len() as u16).to_le_bytes());
+        chunk[11..13].copy_from_slice(&block_type.to_le_bytes());
+        for (i, code) in codes.iter().enumerate() {
+            chunk[13 + i * 7..19 + i * 7].copy_from_slice(code.as_bytes());
+        }
+        file.extend_from_slice(&chunk);
+    }
+
+    // 文件被分成两个片段下载，每个片段的响应前 4 字节未知
+    let mut block = BlockInfo::default();
+    let mut downloaded = Vec::new();
+    for piece in file.chunks(3000) {
+        let mut v = vec![0; 4];
+        v.extend_from_slice(piece);
+        block.parse(v).unwrap();
+        downloaded.extend_from_slice(block.result());
+    }
+    assert_eq!(downloaded, file);
+
+    let blocks = BlockData::parse(&downloaded);
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].block_name, "沪深300");
+    assert_eq!(blocks[0].block_type, 2);
+    assert_eq!(blocks[0].codes, ["000001", "600000"]);
+    assert_eq!(blocks[1].block_name, "军工");
+    assert_eq!(blocks[1].block_type, 4);
+    assert_eq!(blocks[1].codes, ["600760"]);
+}
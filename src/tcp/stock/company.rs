@@ -0,0 +1,388 @@
+use crate::tcp::{
+    helper::{code_bytes, filename_field},
+    Tdx,
+};
+
+/// 查询股票 F10 资料的目录。对应于 pytdx 中的 hq.get_company_info_category、
+/// GetCompanyInfoCategory。
+///
+/// 目录中的每一项记录了文件名、起始位置和长度，把它们传给 [`CompanyInfoContent`]
+/// 即可获取该项的文本内容。
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct CompanyInfoCategory<'d> {
+    pub send: Box<[u8]>,
+    pub market: u16,
+    pub code: &'d str,
+    /// 响应信息中的目录项数量。
+    pub count: usize,
+    pub response: Vec<u8>,
+    pub data: Vec<CompanyInfoCategoryData>,
+}
+
+impl<'d> Default for CompanyInfoCategory<'d> {
+    fn default() -> Self {
+        Self {
+            send: {
+                let mut arr = [0; Self::LEN];
+                arr.copy_from_slice(Self::SEND);
+                arr.into()
+            },
+            market: 0,
+            code: "000001",
+            count: 0,
+            response: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+}
+
+impl<'d> CompanyInfoCategory<'d> {
+    /// 0 代表深市；1 代表沪市。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
+    pub fn new(market: u16, code: &'d str) -> crate::Result<Self> {
+        let mut category = Self::default();
+        category.market(market).code(code)?;
+        Ok(category)
+    }
+
+    /// 修改市场。
+    pub fn market(&mut self, market: u16) -> &mut Self {
+        self.market = market;
+        self.send[12..14].copy_from_slice(&market.to_le_bytes());
+        self
+    }
+
+    /// 修改股票。当代码不正确时，不能正常得到响应。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        self.send[14..20].copy_from_slice(&code_bytes(code)?);
+        self.code = code;
+        Ok(self)
+    }
+}
+
+impl<'d> Tdx for CompanyInfoCategory<'d> {
+    type Item = [CompanyInfoCategoryData];
+
+    /// #sz000001# F10 目录的请求字节。长度为 24。
+    /// ```python
+    /// struct.pack("<H6sI", bytes) # 后 12 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x0c, 0x0f, 0x10, 0x9b, 0x00, 0x01, 0x0e, 0x00, 0x0e, 0x00, 0xcf, 0x02, 0x00, 0x00, 0x30,
+        0x30, 0x30, 0x30, 0x30, 0x31, 0x00, 0x00, 0x00, 0x00,
+    ];
+    const TAG: &'static str = "F10 目录";
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 2 字节表示目录项数量，剩余字节中，每 152 字节使用 [`CompanyInfoCategoryData::parse`]
+    /// 解析。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        use crate::tcp::helper::need;
+        need(&v, 2)?;
+        self.count = crate::bytes_helper::u16_from_le_bytes(&v, 0) as usize;
+        need(&v, 2 + self.count * 152)?;
+        self.data = v[2..]
+            .chunks_exact(152)
+            .take(self.count)
+            .map(CompanyInfoCategoryData::parse)
+            .collect();
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// [`CompanyInfoCategory`] 的解析结果：F10 目录中的一项。
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct CompanyInfoCategoryData {
+    /// 目录名称，比如 `最新提示`
+    pub name: String,
+    /// 存储内容的文件名，比如 `000001.txt`
+    pub filename: String,
+    /// 内容在文件中的起始位置
+    pub start: u32,
+    /// 内容的字节长度
+    pub length: u32,
+}
+
+impl CompanyInfoCategoryData {
+    /// 解析 [`CompanyInfoCategory`] 的响应字节。传入长度为 152 字节序列。
+    /// ```python
+    /// (
+    ///     name,     # GBK 编码，以 \x00 结尾
+    ///     filename, # GBK 编码，以 \x00 结尾
+    ///     start,
+    ///     length,
+    /// ) = struct.unpack("<64s80sII", bytes) # python 表示方式
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Self {
+        use crate::{bytes_helper::u32_from_le_bytes, tcp::helper::gbk_until_nul};
+        Self {
+            name: gbk_until_nul(&bytes[..64]),
+            filename: gbk_until_nul(&bytes[64..144]),
+            start: u32_from_le_bytes(bytes, 144),
+            length: u32_from_le_bytes(bytes, 148),
+        }
+    }
+}
+
+/// 查询股票 F10 资料的某一项内容。对应于 pytdx 中的 hq.get_company_info_content、
+/// GetCompanyInfoContent。
+///
+/// 参数通常来自 [`CompanyInfoCategory`] 的结果，使用 [`CompanyInfoContent::from_category`]
+/// 构造即可。
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct CompanyInfoContent<'d> {
+    pub send: Box<[u8]>,
+    pub market: u16,
+    pub code: &'d str,
+    pub filename: &'d str,
+    pub start: u32,
+    pub length: u32,
+    pub response: Vec<u8>,
+    /// 解码后的文本内容
+    pub data: String,
+}
+
+/// 为了对应 [`CompanyInfoContent::SEND`] 的含义，以下默认值值得注意：
+/// 1. filename 默认为 `000001.txt`；
+/// 2. start 默认为 0；
+/// 3. length 默认为 10000。
+impl<'d> Default for CompanyInfoContent<'d> {
+    fn default() -> Self {
+        Self {
+            send: {
+                let mut arr = [0; Self::LEN];
+                arr.copy_from_slice(Self::SEND);
+                arr.into()
+            },
+            market: 0,
+            code: "000001",
+            filename: "000001.txt",
+            start: 0,
+            length: 10000,
+            response: Vec::new(),
+            data: String::new(),
+        }
+    }
+}
+
+impl<'d> CompanyInfoContent<'d> {
+    /// 0 代表深市；1 代表沪市。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]；
+    /// 当 filename 的字节长度超过 80 时，返回 [`Error::InvalidFilename`][crate::Error::InvalidFilename]。
+    pub fn new(
+        market: u16,
+        code: &'d str,
+        filename: &'d str,
+        start: u32,
+        length: u32,
+    ) -> crate::Result<Self> {
+        let mut content = Self::default();
+        content
+            .market(market)
+            .code(code)?
+            .filename(filename)?
+            .start(start)
+            .length(length);
+        Ok(content)
+    }
+
+    /// 使用 [`CompanyInfoCategory`] 的某一项结果构造查询。错误与 [`CompanyInfoContent::new`] 相同。
+    pub fn from_category(
+        market: u16,
+        code: &'d str,
+        category: &'d CompanyInfoCategoryData,
+    ) -> crate::Result<Self> {
+        Self::new(
+            market,
+            code,
+            &category.filename,
+            category.start,
+            category.length,
+        )
+    }
+
+    /// 修改市场。
+    pub fn market(&mut self, market: u16) -> &mut Self {
+        self.market = market;
+        self.send[12..14].copy_from_slice(&market.to_le_bytes());
+        self
+    }
+
+    /// 修改股票。当代码不正确时，不能正常得到响应。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        self.send[14..20].copy_from_slice(&code_bytes(code)?);
+        self.code = code;
+        Ok(self)
+    }
+
+    /// 修改文件名。
+    ///
+    /// 当 filename 的字节长度超过 80 时，返回 [`Error::InvalidFilename`][crate::Error::InvalidFilename]，
+    /// 且不修改任何内容。
+    pub fn filename(&mut self, filename: &'d str) -> crate::Result<&mut Self> {
+        filename_field(&mut self.send[22..102], filename)?;
+        self.filename = filename;
+        Ok(self)
+    }
+
+    /// 修改起始位置。
+    pub fn start(&mut self, start: u32) -> &mut Self {
+        self.start = start;
+        self.send[102..106].copy_from_slice(&start.to_le_bytes());
+        self
+    }
+
+    /// 修改内容长度。
+    pub fn length(&mut self, length: u32) -> &mut Self {
+        self.length = length;
+        self.send[106..110].copy_from_slice(&length.to_le_bytes());
+        self
+    }
+}
+
+impl<'d> Tdx for CompanyInfoContent<'d> {
+    type Item = str;
+
+    /// #sz000001# 的 `000001.txt` 从 0 开始 10000 字节内容的请求字节。长度为 114。
+    /// ```python
+    /// struct.pack("<H6sH80sIII", bytes) # 后 102 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x0c, 0x07, 0x10, 0x9c, 0x00, 0x01, 0x68, 0x00, 0x68, 0x00, 0xd0, 0x02, 0x00, 0x00, 0x30,
+        0x30, 0x30, 0x30, 0x30, 0x31, 0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x30, 0x31, 0x2e, 0x74,
+        0x78, 0x74, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x10, 0x27, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    const TAG: &'static str = "F10 内容";
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 10 字节未知，之后 2 字节表示内容长度，剩余字节为 GBK 编码的文本。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        use crate::tcp::helper::need;
+        need(&v, 12)?;
+        let len = crate::bytes_helper::u16_from_le_bytes(&v, 10) as usize;
+        need(&v, 12 + len)?;
+        self.data = crate::bytes_helper::gbk_lossy(&v[12..12 + len]);
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+#[test]
+fn company_new_modify() -> crate::Result<()> {
+    let category = CompanyInfoCategory::new(0, "000001")?;
+    let mut category2 = CompanyInfoCategory::new(1, "600000")?;
+    category2.market(0).code("000001")?;
+    compare!(CompanyInfoCategory::default(), category, category2);
+
+    let content = CompanyInfoContent::new(0, "000001", "000001.txt", 0, 10000)?;
+    let mut content2 = CompanyInfoContent::new(1, "600000", "600000_long_name.txt", 10, 1)?;
+    content2
+        .market(0)
+        .code("000001")?
+        .filename("000001.txt")?
+        .start(0)
+        .length(10000);
+    compare!(CompanyInfoContent::default(), content, content2);
+
+    let long = "0".repeat(81);
+    let err = content2.filename(&long).unwrap_err();
+    assert!(
+        matches!(err, crate::Error::InvalidFilename { max: 80, .. }),
+        "{err}"
+    );
+    compare!(CompanyInfoContent::default(), content2);
+    Ok(())
+}
+
+#[test]
+fn connection() -> crate::Result<()> {
+    crate::tcp::tests::connection(CompanyInfoCategory::default())?;
+    crate::tcp::tests::connection(CompanyInfoContent::default())
+}
+
+#[test]
+fn parse() {
+    let entries = [
+        ("最新提示", "000001.txt", 0, 25466),
+        ("公司概况", "000001.txt", 25466, 9713),
+    ];
+    let mut arr = vec![0x02, 0x00];
+    for (name, filename, start, length) in entries {
+        let mut entry = [0; 152];
+        let name = encoding_rs::GBK.encode(name).0;
+        entry[..name.len()].copy_from_slice(&name);
+        entry[64..64 + filename.len()].copy_from_slice(filename.as_bytes());
+        entry[144..148].copy_from_slice(&u32::to_le_bytes(start));
+        entry[148..152].copy_from_slice(&u32::to_le_bytes(length));
+        arr.extend_from_slice(&entry);
+    }
+    let mut category = CompanyInfoCategory::default();
+    crate::tcp::tests::truncated(&mut category, &arr);
+    category.parse(arr).unwrap();
+    assert_eq!(category.count, 2);
+    for (data, (name, filename, start, length)) in category.data.iter().zip(entries) {
+        assert_eq!(data.name, name);
+        assert_eq!(data.filename, filename);
+        assert_eq!(data.start, start);
+        assert_eq!(data.length, length);
+    }
+
+    let content = CompanyInfoContent::from_category(0, "000001", &category.data[1]).unwrap();
+    assert_eq!(
+        &content.send[102..110],
+        &[0x7a, 0x63, 0, 0, 0xf1, 0x25, 0, 0]
+    );
+
+    // 内容：`【1.基本资料】` 的 GBK 编码，以及一个多余的字节
+    let mut content = CompanyInfoContent::default();
+    content
+        .parse(vec![
+            0x00, 0x30, 0x30, 0x30, 0x30, 0x30, 0x31, 0x00, 0x00, 0x00, 0x0e, 0x00, 0xa1, 0xbe,
+            0x31, 0x2e, 0xbb, 0xf9, 0xb1, 0xbe, 0xd7, 0xca, 0xc1, 0xcf, 0xa1, 0xbf, 0x0d,
+        ])
+        .unwrap();
+    assert_eq!(content.result(), "【1.基本资料】");
+
+    // 无效的 GBK 字节被替换成 U+FFFD，而不是 panic
+    content
+        .parse(vec![
+            0x00, 0x30, 0x30, 0x30, 0x30, 0x30, 0x31, 0x00, 0x00, 0x00, 0x04, 0x00, 0xa1, 0xbe,
+            0xff, 0x31,
+        ])
+        .unwrap();
+    assert_eq!(content.result(), "【\u{FFFD}1");
+}
@@ -0,0 +1,289 @@
+use crate::tcp::{helper::code_bytes, Tdx};
+
+/// 对应 pytdx 的 hq.get_finance_info、GetFinanceInfo。获取单只股票的财务摘要。
+///
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct FinanceInfo<'d> {
+    pub send: Box<[u8]>,
+    pub market: u16,
+    pub code: &'d str,
+    pub response: Vec<u8>,
+    pub data: FinanceInfoData,
+}
+
+impl<'d> Default for FinanceInfo<'d> {
+    fn default() -> Self {
+        Self {
+            send: {
+                let mut arr = [0; Self::LEN];
+                arr.copy_from_slice(Self::SEND);
+                arr.into()
+            },
+            market: 0,
+            code: "000001",
+            response: Vec::new(),
+            data: FinanceInfoData::default(),
+        }
+    }
+}
+
+impl<'d> FinanceInfo<'d> {
+    /// 0 代表深市；1 代表沪市。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
+    pub fn new(market: u16, code: &'d str) -> crate::Result<Self> {
+        let mut finance = Self::default();
+        finance.market(market).code(code)?;
+        Ok(finance)
+    }
+
+    /// 修改市场。
+    pub fn market(&mut self, market: u16) -> &mut Self {
+        self.market = market;
+        self.send[14] = market as u8;
+        self
+    }
+
+    /// 修改股票。当代码不正确时，不能正常得到响应。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        self.send[15..21].copy_from_slice(&code_bytes(code)?);
+        self.code = code;
+        Ok(self)
+    }
+}
+
+impl<'a> Tdx for FinanceInfo<'a> {
+    type Item = FinanceInfoData;
+
+    // market=0; code="000001"
+    const SEND: &'static [u8] = &[
+        0x0c, 0x1f, 0x18, 0x76, 0x00, 0x01, 0x0b, 0x00, 0x0b, 0x00, 0x10, 0x00, 0x01, 0x00, 0x00,
+        0x30, 0x30, 0x30, 0x30, 0x30, 0x31,
+    ];
+    const TAG: &'static str = "财务信息";
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 2 字节为股票数量（只查询一只，所以总是 1），之后使用 [`FinanceInfoData::parse`] 解析。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        crate::tcp::helper::need(&v, 2 + FinanceInfoData::LEN)?;
+        self.data = FinanceInfoData::parse(&v[2..]);
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// [`FinanceInfo`] 的解析结果。
+///
+/// 除了特别说明的字段，股本的单位为股，金额的单位为元。
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct FinanceInfoData {
+    pub market: u8,
+    /// 6 位股票代码
+    pub code: String,
+    /// 流通股本
+    pub liutongguben: f64,
+    /// 所属省份
+    pub province: u16,
+    /// 所属行业
+    pub industry: u16,
+    /// 财务数据的更新日期
+    pub updated_date: u32,
+    /// 上市日期
+    pub ipo_date: u32,
+    /// 总股本
+    pub zongguben: f64,
+    /// 国家股
+    pub guojiagu: f64,
+    /// 发起人法人股
+    pub faqirenfarengu: f64,
+    /// 法人股
+    pub farengu: f64,
+    /// B 股
+    pub bgu: f64,
+    /// H 股
+    pub hgu: f64,
+    /// 职工股
+    pub zhigonggu: f64,
+    /// 总资产
+    pub zongzichan: f64,
+    /// 流动资产
+    pub liudongzichan: f64,
+    /// 固定资产
+    pub gudingzichan: f64,
+    /// 无形资产
+    pub wuxingzichan: f64,
+    /// 股东人数（未换算单位）
+    pub gudongrenshu: f64,
+    /// 流动负债
+    pub liudongfuzhai: f64,
+    /// 长期负债
+    pub changqifuzhai: f64,
+    /// 资本公积金
+    pub zibengongjijin: f64,
+    /// 净资产
+    pub jingzichan: f64,
+    /// 主营收入
+    pub zhuyingshouru: f64,
+    /// 主营利润
+    pub zhuyinglirun: f64,
+    /// 应收账款
+    pub yingshouzhangkuan: f64,
+    /// 营业利润
+    pub yingyelirun: f64,
+    /// 投资收益
+    pub touzishouyu: f64,
+    /// 经营现金流
+    pub jingyingxianjinliu: f64,
+    /// 总现金流
+    pub zongxianjinliu: f64,
+    /// 存货
+    pub cunhuo: f64,
+    /// 利润总额
+    pub lirunzonghe: f64,
+    /// 税后利润
+    pub shuihoulirun: f64,
+    /// 净利润
+    pub jinglirun: f64,
+    /// 未分配利润
+    pub weifenlirun: f64,
+    /// 每股净资产，单位：元
+    pub meigujingzichan: f64,
+    /// 保留字段
+    pub baoliu2: f64,
+}
+
+impl FinanceInfoData {
+    /// 解析的字节长度：7 字节的市场和代码，以及 136 字节的财务数据。
+    pub const LEN: usize = 143;
+
+    /// 解析方式：
+    /// ```python
+    /// (market, code) = struct.unpack("<B6s", bytes[:7])
+    /// (liutongguben, province, industry, updated_date, ipo_date, zongguben, ...)
+    ///     = struct.unpack("<fHHIIffffffffffffffffffffffffffffff", bytes[7:])
+    /// ```
+    ///
+    /// 股本和金额类数据在原始响应中以万为单位，这里乘以 10000 换算。
+    /// ## 注意
+    /// `bytes` 为长度 143 的 slice
+    pub fn parse(bytes: &[u8]) -> Self {
+        use crate::bytes_helper::{
+            f32_from_le_bytes, u16_from_le_bytes, u32_from_le_bytes, u8_from_le_bytes,
+        };
+        let raw = |i: usize| f32_from_le_bytes(bytes, 23 + i * 4) as f64;
+        let wan = |i: usize| raw(i) * 10000.;
+        Self {
+            market: u8_from_le_bytes(bytes, 0),
+            code: String::from_utf8_lossy(&bytes[1..7]).into_owned(),
+            liutongguben: f32_from_le_bytes(bytes, 7) as f64 * 10000.,
+            province: u16_from_le_bytes(bytes, 11),
+            industry: u16_from_le_bytes(bytes, 13),
+            updated_date: u32_from_le_bytes(bytes, 15),
+            ipo_date: u32_from_le_bytes(bytes, 19),
+            zongguben: wan(0),
+            guojiagu: wan(1),
+            faqirenfarengu: wan(2),
+            farengu: wan(3),
+            bgu: wan(4),
+            hgu: wan(5),
+            zhigonggu: wan(6),
+            zongzichan: wan(7),
+            liudongzichan: wan(8),
+            gudingzichan: wan(9),
+            wuxingzichan: wan(10),
+            gudongrenshu: raw(11),
+            liudongfuzhai: wan(12),
+            changqifuzhai: wan(13),
+            zibengongjijin: wan(14),
+            jingzichan: wan(15),
+            zhuyingshouru: wan(16),
+            zhuyinglirun: wan(17),
+            yingshouzhangkuan: wan(18),
+            yingyelirun: wan(19),
+            touzishouyu: wan(20),
+            jingyingxianjinliu: wan(21),
+            zongxianjinliu: wan(22),
+            cunhuo: wan(23),
+            lirunzonghe: wan(24),
+            shuihoulirun: wan(25),
+            jinglirun: wan(26),
+            weifenlirun: wan(27),
+            meigujingzichan: raw(28),
+            baoliu2: raw(29),
+        }
+    }
+}
+
+#[test]
+fn finance_new_modify() -> crate::Result<()> {
+    let finance = FinanceInfo::new(0, "000001")?;
+    let mut finance2 = FinanceInfo::new(1, "600000")?;
+    finance2.market(0).code("000001")?;
+    compare!(FinanceInfo::default(), finance, finance2);
+    Ok(())
+}
+
+#[test]
+fn connection() -> crate::Result<()> {
+    crate::tcp::tests::connection(FinanceInfo::default())
+}
+
+#[test]
+fn parse() {
+    let target = FinanceInfoData {
+        market: 0,
+        code: "000001".into(),
+        liutongguben: 19405752500.0,
+        province: 18,
+        industry: 1,
+        updated_date: 20210830,
+        ipo_date: 19910403,
+        zongguben: 19405917500.0,
+        zongzichan: 4930020000.0,
+        gudingzichan: 1080000.0,
+        wuxingzichan: 105000.0,
+        gudongrenshu: 433.5,
+        zibengongjijin: 80962500.0,
+        jingzichan: 402345000.0,
+        zhuyingshouru: 84965000.0,
+        yingyelirun: 32512500.0,
+        jingyingxianjinliu: -76230000.0,
+        zongxianjinliu: 112340000.0,
+        lirunzonghe: 32515000.0,
+        shuihoulirun: 25347500.0,
+        jinglirun: 25345000.0,
+        weifenlirun: 219870000.0,
+        meigujingzichan: 20.75,
+        ..Default::default()
+    };
+    let mut finance = FinanceInfo::default();
+    finance
+        .parse(vec![
+            0x01, 0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x30, 0x31, 0xfa, 0xe2, 0xec, 0x49, 0x12,
+            0x00, 0x01, 0x00, 0x8e, 0x64, 0x34, 0x01, 0x03, 0xcf, 0x2f, 0x01, 0x7e, 0xe3, 0xec,
+            0x49, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0xb9, 0xf0,
+            0x48, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xd8, 0x42, 0x00, 0x00, 0x28, 0x41, 0x00,
+            0xc0, 0xd8, 0x43, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xfd,
+            0x45, 0x80, 0x2a, 0x1d, 0x47, 0x00, 0xc2, 0x04, 0x46, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x34, 0x4b, 0x45, 0x00, 0x00, 0x00, 0x00, 0x00, 0x38, 0xee,
+            0xc5, 0x00, 0x88, 0x2f, 0x46, 0x00, 0x00, 0x00, 0x00, 0x00, 0x38, 0x4b, 0x45, 0x00,
+            0x6c, 0x1e, 0x45, 0x00, 0x68, 0x1e, 0x45, 0x00, 0xc6, 0xab, 0x46, 0x00, 0x00, 0xa6,
+            0x41, 0x00, 0x00, 0x00, 0x00,
+        ])
+        .unwrap();
+    compare!(finance.data, target);
+}
@@ -0,0 +1,239 @@
+use super::kline::real_price;
+use crate::tcp::{
+    helper::{code_bytes, DateTime},
+    Tdx,
+};
+
+/// 查询指数 K 线。对应于 pytdx 中的 hq.get_index_bars、GetIndexBarsCmd。
+///
+/// 请求字节与 [`Kline`][super::Kline] 完全一致，但响应中每根 K 线多出上涨家数和下跌家数，
+/// 所以指数必须使用此类型解析，否则数据会错位。
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+/// 比如修改指数代码调用 [`IndexKline::code`]，修改查询数量调用 [`IndexKline::count`]。
+#[derive(Debug, Clone)]
+pub struct IndexKline<'d> {
+    pub send: Box<[u8]>,
+    pub market: u16,
+    pub code: &'d str,
+    pub category: u16,
+    pub start: u16,
+    pub count: u16,
+    pub response: Vec<u8>,
+    pub data: Vec<IndexKlineData<'d>>,
+}
+
+/// 为了对应 [`IndexKline::SEND`] 的含义，以下默认值值得注意：
+/// 1. market 默认为 1 （沪市）；
+/// 2. category 默认为 9 （日线）；
+/// 3. code 默认为 `000001` （上证指数）；
+/// 4. count 默认为 3；
+/// 5. IndexKlineData.dt.hour 默认小时数为 15。
+#[rustfmt::skip]
+impl<'d> Default for IndexKline<'d> {
+    fn default() -> Self {
+        Self { market: 1, code: "000001", category: 9, start: 0, count: 3,
+               send:     { let mut v = [0; Self::LEN]; v.copy_from_slice(Self::SEND); v.into() },
+               response: Vec::new(),
+               data:     vec![IndexKlineData::default(); 3], }
+    }
+}
+
+impl<'d> IndexKline<'d> {
+    /// 0 代表深市；1 代表沪市。category 的含义见 [`IndexKline::category`]。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
+    pub fn new(
+        market: u16,
+        code: &'d str,
+        category: u16,
+        start: u16,
+        count: u16,
+    ) -> crate::Result<Self> {
+        let mut kline = Self::default();
+        kline
+            .market(market)
+            .code(code)?
+            .category(category)
+            .start(start)
+            .count(count);
+        Ok(kline)
+    }
+
+    /// 修改市场。
+    pub fn market(&mut self, market: u16) -> &mut Self {
+        self.market = market;
+        self.send[12..14].copy_from_slice(&market.to_le_bytes());
+        self
+    }
+
+    /// 修改指数。当代码不正确时，不能正常得到响应。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        self.send[14..20].copy_from_slice(&code_bytes(code)?);
+        self.code = code;
+        Ok(self)
+    }
+
+    /// 修改 K 线类型：
+    ///
+    /// |   | 类型         |    | 类型         |
+    /// | - | ------------ | -- | ------------ |
+    /// | 0 | 5 分钟 K 线  | 6  | 月 K 线      |
+    /// | 1 | 15 分钟 K 线 | 7  | 1 分钟       |
+    /// | 2 | 30 分钟 K 线 | 8  | 1 分钟 K 线  |
+    /// | 3 | 1 小时 K 线  | 9  | 日 K 线      |
+    /// | 4 | 日 K 线      | 10 | 季 K 线      |
+    /// | 5 | 周 K 线      | 11 | 年 K 线      |
+    pub fn category(&mut self, category: u16) -> &mut Self {
+        self.category = category;
+        self.send[20..22].copy_from_slice(&category.to_le_bytes());
+        self
+    }
+
+    /// 修改起始位置。
+    pub fn start(&mut self, start: u16) -> &mut Self {
+        self.start = start;
+        self.send[24..26].copy_from_slice(&start.to_le_bytes());
+        self
+    }
+
+    /// 修改查询数量。与 [`Kline::count`][super::Kline::count] 一样，
+    /// 会保证查询数量与 data 字段的 Vec 长度一致。
+    pub fn count(&mut self, count: u16) -> &mut Self {
+        self.data.resize_with(count as usize, Default::default);
+        self.count = count;
+        self.send[26..28].copy_from_slice(&count.to_le_bytes());
+        self
+    }
+}
+
+impl<'a> Tdx for IndexKline<'a> {
+    type Item = [IndexKlineData<'a>];
+
+    /// #sh000001# 最近三天日线的请求字节。长度为 38。
+    /// ```python
+    /// struct.pack("<HIHHHH6sHHHHIIH", bytes) # python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x0c, 0x01, 0x08, 0x64, 0x01, 0x01, 0x1c, 0x00, 0x1c, 0x00, 0x2d, 0x05, 0x01, 0x00, 0x30,
+        0x30, 0x30, 0x30, 0x30, 0x31, 0x09, 0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    const TAG: &'static str = "指数 K 线";
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 与 [`Kline`][super::Kline] 的解析方式相同，只是每根 K 线最后多出 4 字节：
+    /// 上涨家数 (u16) 和下跌家数 (u16) 。
+    #[rustfmt::skip]
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        use crate::{
+            tcp::helper::{datetime, need, price, vol_amount},
+            bytes_helper::{u16_from_le_bytes, u32_from_le_bytes}
+        };
+
+        need(&v, 2)?;
+        let (count, mut pos, mut base) = (u16_from_le_bytes(&v, 0), 2, 0);
+        self.data.resize_with(count as usize, Default::default);
+        for item in self.data.iter_mut() {
+            need(&v, pos + 4)?;
+            let dt = datetime(&v[pos..pos + 4], self.category);
+            pos += 4;
+            let open = price(&v, &mut pos)?;
+            let close = price(&v, &mut pos)?;
+            let (high, low) = (price(&v, &mut pos)?, price(&v, &mut pos)?);
+            need(&v, pos + 12)?;
+
+            *item = IndexKlineData { dt, code: self.code,
+                                     open:       { base += open; base as f64 / 1000. },
+                                     close:      real_price(close, base),
+                                     high:       real_price(high, base),
+                                     low:        real_price(low, base),
+                                     vol:        { pos += 4; vol_amount(u32_from_le_bytes(&v, pos - 4) as i32) },
+                                     amount:     { pos += 4; vol_amount(u32_from_le_bytes(&v, pos - 4) as i32) },
+                                     up_count:   { pos += 2; u16_from_le_bytes(&v, pos - 2) },
+                                     down_count: { pos += 2; u16_from_le_bytes(&v, pos - 2) }};
+
+            base += close;
+        }
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// [`IndexKline`] 的解析结果。
+#[derive(Debug, Default, Clone, serde::Serialize)]
+pub struct IndexKlineData<'d> {
+    pub dt: DateTime,
+    pub code: &'d str,
+    pub open: f64,
+    pub close: f64,
+    pub high: f64,
+    pub low: f64,
+    /// 成交量，单位：股
+    pub vol: f64,
+    /// 成交额，单位：元
+    pub amount: f64,
+    /// 上涨家数
+    pub up_count: u16,
+    /// 下跌家数
+    pub down_count: u16,
+}
+
+#[test]
+fn index_new_modify() -> crate::Result<()> {
+    let index = IndexKline::new(1, "000001", 9, 0, 3)?;
+    let mut index2 = IndexKline::new(0, "399001", 0, 1, 1)?;
+    index2
+        .market(1)
+        .code("000001")?
+        .category(9)
+        .start(0)
+        .count(3);
+    compare!(IndexKline::default(), index, index2);
+    Ok(())
+}
+
+#[test]
+fn connection() -> crate::Result<()> {
+    crate::tcp::tests::connection(IndexKline::default())
+}
+
+#[test]
+fn parse() {
+    let mut index = IndexKline::default();
+    let arr = vec![
+        0x03, 0x00, 0xeb, 0x64, 0x34, 0x01, 0xb4, 0x9a, 0x02, 0xe4, 0x06, 0x9c, 0x03, 0xc2, 0x07,
+        0xe8, 0x6f, 0xa8, 0x49, 0x59, 0xf7, 0x12, 0x4f, 0x2c, 0x04, 0x9a, 0x02, 0xec, 0x64, 0x34,
+        0x01, 0xd0, 0x01, 0xfa, 0x03, 0x90, 0x01, 0xc4, 0x04, 0x00, 0x81, 0x9a, 0x49, 0xb7, 0xb1,
+        0x03, 0x4f, 0x4f, 0x01, 0x93, 0x05, 0xef, 0x64, 0x34, 0x01, 0xcc, 0x02, 0xa8, 0x05, 0x96,
+        0x07, 0xd6, 0x02, 0xd8, 0x3d, 0x8b, 0x49, 0x4b, 0xf0, 0xeb, 0x4e, 0x1b, 0x06, 0x7c, 0x00,
+    ];
+    #[rustfmt::skip]
+    let dt = |day| DateTime { year: 2021, month: 9, day, hour: 15, minute: 0 };
+    #[rustfmt::skip]
+    let res = [
+        IndexKlineData { dt: dt(23), code: "000001", open: 18.1, close: 17.68, high: 18.32,
+                         low: 17.65, vol: 1379837.0, amount: 2465683712.0,
+                         up_count: 1068, down_count: 666 },
+        IndexKlineData { dt: dt(24), code: "000001", open: 17.6, close: 17.35, high: 17.68,
+                         low: 17.34, vol: 1265696.0, amount: 2209462016.0,
+                         up_count: 335, down_count: 1427 },
+        IndexKlineData { dt: dt(27), code: "000001", open: 17.21, close: 17.57, high: 17.68,
+                         low: 17.06, vol: 1140667.0, amount: 1979196800.0,
+                         up_count: 1563, down_count: 124 },
+    ];
+    crate::tcp::tests::truncated(&mut index, &arr);
+    index.parse(arr).unwrap();
+    compare!(res, index.data.as_slice());
+}
@@ -1,4 +1,10 @@
-use crate::tcp::{helper::DateTime, Tdx};
+use crate::tcp::{
+    helper::{code_bytes, DateTime},
+    Tcp, Tdx,
+};
+
+/// 单次 [`Kline`] 请求最多返回的 K 线数量。
+pub const KLINE_MAX_COUNT: u16 = 800;
 
 // ['获取股票行情', '参数：市场代码， 股票代码， 如： 0,000001 或 1,  600300',
 // get_security_quotes, '0,000001']),          (2, ['获取k线', '''category-> K线种类  0
@@ -48,23 +54,25 @@ impl<'d> Default for Kline<'d> {
 impl<'d> Kline<'d> {
     /// 0 代表深市；1 代表沪市。
     ///
-    /// ## panic
-    /// 当 code 的字节长度不是 6 时，程序会 panic。
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
     #[rustfmt::skip]
-    pub fn new(market: u16, code: &'d str, category: u16, start: u16, count: u16) -> Self {
-        Self { market, code, category, start, count,
-               send: {
-                   let mut arr = [0; Self::LEN];
-                   arr.copy_from_slice(Self::SEND);
-                   arr[12..14].copy_from_slice(&market.to_le_bytes());
-                   arr[14..20].copy_from_slice(code.as_bytes());
-                   arr[20..22].copy_from_slice(&category.to_le_bytes());
-                   arr[24..26].copy_from_slice(&start.to_le_bytes());
-                   arr[26..28].copy_from_slice(&count.to_le_bytes());
-                   arr.into()
-               },
-               response: Vec::new(),
-               data: vec![KlineData::default(); count as usize] }
+    pub fn new(
+        market: u16, code: &'d str, category: u16, start: u16, count: u16,
+    ) -> crate::Result<Self> {
+        let code_arr = code_bytes(code)?;
+        Ok(Self { market, code, category, start, count,
+                  send: {
+                      let mut arr = [0; Self::LEN];
+                      arr.copy_from_slice(Self::SEND);
+                      arr[12..14].copy_from_slice(&market.to_le_bytes());
+                      arr[14..20].copy_from_slice(&code_arr);
+                      arr[20..22].copy_from_slice(&category.to_le_bytes());
+                      arr[24..26].copy_from_slice(&start.to_le_bytes());
+                      arr[26..28].copy_from_slice(&count.to_le_bytes());
+                      arr.into()
+                  },
+                  response: Vec::new(),
+                  data: vec![KlineData::default(); count as usize] })
     }
 
     /// 修改市场。
@@ -76,12 +84,12 @@ impl<'d> Kline<'d> {
 
     /// 修改股票。当代码不正确时，不能正常得到响应。
     ///
-    /// ## panic
-    /// 当 code 的字节长度不是 6 时，程序会 panic。
-    pub fn code(&mut self, code: &'d str) -> &mut Self {
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        self.send[14..20].copy_from_slice(&code_bytes(code)?);
         self.code = code;
-        self.send[14..20].copy_from_slice(code.as_bytes());
-        self
+        Ok(self)
     }
 
     /// 修改 K 线类型。
@@ -112,6 +120,54 @@ impl<'d> Kline<'d> {
         self.send[26..28].copy_from_slice(&count.to_le_bytes());
         self
     }
+
+    /// 以 [`KLINE_MAX_COUNT`] 为步长从最新的 K 线往前翻页，获取当前市场、股票和 K
+    /// 线类型的全部历史 K 线，直到上市首日或者 range 的起始日期。
+    ///
+    /// range 为 `(起始日期, 结束日期)`，日期为 `20210923` 形式的 u32，两端都包含；
+    /// `None` 表示获取全部 K 线。返回的 K 线已去除重复（翻页期间产生新的 K
+    /// 线会导致相邻两页有重叠），并按时间先后排序。
+    ///
+    /// ## 注意
+    /// 此方法会修改 start 和 count 。由于 start 为 u16 ，最多只能往前翻 65535 根 K
+    /// 线，超出时返回 [`Error::Custom`][crate::Error::Custom]，而不是返回不完整的历史；
+    /// 此时可以缩小 range 。服务器能提供的分钟 K 线往往也有限。
+    pub fn recv_history(
+        &mut self,
+        tcp: &mut Tcp,
+        range: Option<(u32, u32)>,
+    ) -> crate::Result<Vec<KlineData<'d>>> {
+        history(range, |start| {
+            self.start(start).count(KLINE_MAX_COUNT);
+            Ok(self.recv_parsed(tcp)?.to_vec())
+        })
+    }
+}
+
+/// 翻页获取 K 线：page 的参数为起始位置，返回从该位置往前的一页 K 线（按时间先后排列）。
+fn history<'d>(
+    range: Option<(u32, u32)>,
+    mut page: impl FnMut(u16) -> crate::Result<Vec<KlineData<'d>>>,
+) -> crate::Result<Vec<KlineData<'d>>> {
+    let (since, until) = range.unwrap_or((0, u32::MAX));
+    let mut all = Vec::new();
+    let mut start = 0u16;
+    loop {
+        let data = page(start)?;
+        let len = data.len();
+        let reached = data.first().is_none_or(|k| k.dt.to_u32() < since);
+        all.extend(data);
+        if len < KLINE_MAX_COUNT as usize || reached {
+            break;
+        }
+        start = start.checked_add(len as u16).ok_or(crate::Error::Custom(
+            "K 线的 start 超出 u16 范围，无法继续翻页",
+        ))?;
+    }
+    all.retain(|k| (since..=until).contains(&k.dt.to_u32()));
+    all.sort_by_key(|k| k.dt);
+    all.dedup_by_key(|k| k.dt);
+    Ok(all)
 }
 
 impl<'a> Tdx for Kline<'a> {
@@ -133,31 +189,36 @@ impl<'a> Tdx for Kline<'a> {
     }
 
     #[rustfmt::skip]
-    fn parse(&mut self, v: Vec<u8>) {
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
         use crate::{
-            tcp::helper::{datetime, price, vol_amount},
+            tcp::helper::{datetime, need, price, vol_amount},
             bytes_helper::{u16_from_le_bytes, u32_from_le_bytes}
         };
 
+        need(&v, 2)?;
         let (count, mut pos, mut base) = (u16_from_le_bytes(&v, 0), 2, 0);
-        debug_assert_eq!(count, self.count);
+        self.data.resize_with(count as usize, Default::default);
         for item in self.data.iter_mut() {
+            need(&v, pos + 4)?;
             let dt = datetime(&v[pos..pos + 4], self.category);
             pos += 4;
-            let open = price(&v, &mut pos);
-            let close = price(&v, &mut pos);
+            let open = price(&v, &mut pos)?;
+            let close = price(&v, &mut pos)?;
+            let (high, low) = (price(&v, &mut pos)?, price(&v, &mut pos)?);
+            need(&v, pos + 8)?;
 
             *item = KlineData { dt, code: self.code,
                                 open:   { base += open; base as f64 / 1000. },
                                 close:  real_price(close, base),
-                                high:   real_price(price(&v, &mut pos), base),
-                                low:    real_price(price(&v, &mut pos), base),
+                                high:   real_price(high, base),
+                                low:    real_price(low, base),
                                 vol:    { pos += 4; vol_amount(u32_from_le_bytes(&v, pos - 4) as i32) },
                                 amount: { pos += 4; vol_amount(u32_from_le_bytes(&v, pos - 4) as i32) }};
 
             base += close;
         }
         self.response = v;
+        Ok(())
     }
 
     fn result(&self) -> &Self::Item {
@@ -206,21 +267,32 @@ pub struct KlineData<'d> {
 //     }
 // }
 
+/// K 线的价格以 0.001 为单位，与证券的
+/// [`decimal_point`][crate::tcp::SecurityListData::decimal_point] 无关。
 #[inline]
-fn real_price(p: i32, base: i32) -> f64 {
+pub(super) fn real_price(p: i32, base: i32) -> f64 {
     (p + base) as f64 / 1000.
 }
 
 #[test]
-fn day_new_modify() {
-    let day = Kline::new(0, "000001", 9, 0, 3);
-    let mut day2 = Kline::new(1, "000000", 0, 1, 1);
-    day2.market(0).code("000001").category(9).start(0).count(3);
+fn day_new_modify() -> crate::Result<()> {
+    let day = Kline::new(0, "000001", 9, 0, 3)?;
+    let mut day2 = Kline::new(1, "000000", 0, 1, 1)?;
+    day2.market(0).code("000001")?.category(9).start(0).count(3);
     compare!(Kline::default(), day, day2);
+
+    // 代码长度不正确时返回错误，且不修改请求
+    assert!(matches!(
+        Kline::new(0, "0000001", 9, 0, 3),
+        Err(crate::Error::InvalidCode(_))
+    ));
+    assert!(day2.code("00001").is_err());
+    compare!(Kline::default(), day2);
+    Ok(())
 }
 
 #[test]
-fn connection() -> std::io::Result<()> {
+fn connection() -> crate::Result<()> {
     crate::tcp::tests::connection(Kline::default())
 }
 
@@ -284,6 +356,48 @@ fn parse() {
             amount: 1979196800.0,
         },
     ];
-    day.parse(arr);
+    crate::tcp::tests::truncated(&mut day, &arr);
+    day.parse(arr).unwrap();
     compare!(res, day.data.as_slice());
 }
+
+#[test]
+fn history_pages() {
+    // 共 2000 根日线，从 2000 年 1 月 1 日起每天一根；翻到第二页时新增 1 根，导致重叠
+    let bar = |i: u32| KlineData {
+        dt: DateTime {
+            year: 2000 + (i / 365) as u16,
+            month: (i % 365 / 28 + 1) as u16,
+            day: (i % 365 % 28 + 1) as u16,
+            hour: 15,
+            minute: 0,
+        },
+        code: "000001",
+        close: i as f64,
+        ..Default::default()
+    };
+    let fake = |total: u32| {
+        let mut shift = 0;
+        move |start: u16| {
+            let end = total + shift - start as u32;
+            shift = 1;
+            let begin = end.saturating_sub(KLINE_MAX_COUNT as u32);
+            Ok((begin..end).map(bar).collect())
+        }
+    };
+
+    let all = history(None, fake(2000)).unwrap();
+    // 新增的那根 K 线在第一页之后才出现，不在结果中；重叠的 K 线只保留一根
+    assert_eq!(all.len(), 2000);
+    assert!(all.iter().enumerate().all(|(i, k)| k.close == i as f64));
+
+    let (since, until) = (bar(1000).dt.to_u32(), bar(1500).dt.to_u32());
+    let part = history(Some((since, until)), fake(2000)).unwrap();
+    assert_eq!(part.first().unwrap().close, 1000.);
+    assert_eq!(part.last().unwrap().close, 1500.);
+    assert_eq!(part.len(), 501);
+
+    // start 超出 u16 范围时返回错误，而不是截断的历史
+    let err = history(None, fake(100_000)).unwrap_err();
+    assert!(matches!(err, crate::Error::Custom(_)), "{err}");
+}
@@ -0,0 +1,318 @@
+use crate::tcp::{helper::code_bytes, Tdx};
+
+/// 查询股票当日分时数据。对应于 pytdx 中的 hq.get_minute_time_data、GetMinuteTimeData。
+///
+/// 响应数据为 240 个点（9:31 ~ 11:30、13:01 ~ 15:00），盘中只返回已经产生的点。
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct MinuteTime<'d> {
+    pub send: Box<[u8]>,
+    pub market: u16,
+    pub code: &'d str,
+    /// 响应信息中的分时点数量。
+    pub count: usize,
+    pub response: Vec<u8>,
+    pub data: Vec<MinuteTimeData>,
+}
+
+#[rustfmt::skip]
+impl<'d> Default for MinuteTime<'d> {
+    fn default() -> Self {
+        Self { market: 0, code: "000001", count: 0,
+               send:     { let mut v = [0; Self::LEN]; v.copy_from_slice(Self::SEND); v.into() },
+               response: Vec::new(),
+               data:     Vec::with_capacity(240), }
+    }
+}
+
+impl<'d> MinuteTime<'d> {
+    /// 0 代表深市；1 代表沪市。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
+    pub fn new(market: u16, code: &'d str) -> crate::Result<Self> {
+        let mut minute = Self::default();
+        minute.market(market).code(code)?;
+        Ok(minute)
+    }
+
+    /// 修改市场。
+    pub fn market(&mut self, market: u16) -> &mut Self {
+        self.market = market;
+        self.send[12..14].copy_from_slice(&market.to_le_bytes());
+        self
+    }
+
+    /// 修改股票。当代码不正确时，不能正常得到响应。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        self.send[14..20].copy_from_slice(&code_bytes(code)?);
+        self.code = code;
+        Ok(self)
+    }
+}
+
+impl<'d> Tdx for MinuteTime<'d> {
+    type Item = [MinuteTimeData];
+
+    /// #sz000001# 当日分时数据的请求字节。长度为 24。
+    /// ```python
+    /// struct.pack("<H6sI", bytes) # 后 12 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x0c, 0x1b, 0x08, 0x00, 0x01, 0x01, 0x0e, 0x00, 0x0e, 0x00, 0x1d, 0x05, 0x00, 0x00, 0x30,
+        0x30, 0x30, 0x30, 0x30, 0x31, 0x00, 0x00, 0x00, 0x00,
+    ];
+    const TAG: &'static str = "分时";
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 2 字节表示分时点数量，跳过 2 字节未知数据之后使用 [`MinuteTimeData::parse`] 解析。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        self.count = MinuteTimeData::parse(&v, 4, &mut self.data)?;
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// 查询股票历史某日的分时数据。对应于 pytdx 中的 hq.get_history_minute_time_data、
+/// GetHistoryMinuteTimeData。
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct HistoryMinuteTime<'d> {
+    pub send: Box<[u8]>,
+    pub market: u16,
+    pub code: &'d str,
+    /// 年月日，比如 `20210923`
+    pub date: u32,
+    /// 响应信息中的分时点数量。
+    pub count: usize,
+    pub response: Vec<u8>,
+    pub data: Vec<MinuteTimeData>,
+}
+
+#[rustfmt::skip]
+impl<'d> Default for HistoryMinuteTime<'d> {
+    fn default() -> Self {
+        Self { market: 0, code: "000001", date: 20210923, count: 0,
+               send:     { let mut v = [0; Self::LEN]; v.copy_from_slice(Self::SEND); v.into() },
+               response: Vec::new(),
+               data:     Vec::with_capacity(240), }
+    }
+}
+
+impl<'d> HistoryMinuteTime<'d> {
+    /// 0 代表深市；1 代表沪市；date 为年月日，比如 `20210923`。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
+    pub fn new(market: u16, code: &'d str, date: u32) -> crate::Result<Self> {
+        let mut minute = Self::default();
+        minute.market(market).code(code)?.date(date);
+        Ok(minute)
+    }
+
+    /// 修改市场。
+    pub fn market(&mut self, market: u16) -> &mut Self {
+        self.market = market;
+        self.send[16] = market as u8;
+        self
+    }
+
+    /// 修改股票。当代码不正确时，不能正常得到响应。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        self.send[17..23].copy_from_slice(&code_bytes(code)?);
+        self.code = code;
+        Ok(self)
+    }
+
+    /// 修改日期。
+    pub fn date(&mut self, date: u32) -> &mut Self {
+        self.date = date;
+        self.send[12..16].copy_from_slice(&date.to_le_bytes());
+        self
+    }
+}
+
+impl<'d> Tdx for HistoryMinuteTime<'d> {
+    type Item = [MinuteTimeData];
+
+    /// #sz000001# 在 2021-09-23 的分时数据的请求字节。长度为 23。
+    /// ```python
+    /// struct.pack("<IB6s", bytes) # 后 11 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x0c, 0x01, 0x30, 0x00, 0x01, 0x01, 0x0d, 0x00, 0x0d, 0x00, 0xb4, 0x0f, 0xeb, 0x64, 0x34,
+        0x01, 0x00, 0x30, 0x30, 0x30, 0x30, 0x30, 0x31,
+    ];
+    const TAG: &'static str = "历史分时";
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 2 字节表示分时点数量，跳过 4 字节未知数据之后使用 [`MinuteTimeData::parse`] 解析。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        self.count = MinuteTimeData::parse(&v, 6, &mut self.data)?;
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// [`MinuteTime`] 和 [`HistoryMinuteTime`] 的解析结果：一个分时点。
+#[derive(Debug, Default, Clone, serde::Serialize)]
+pub struct MinuteTimeData {
+    /// 分钟数，即从 0 点开始的分钟数，比如 `571` 表示 9:31。
+    pub time: u16,
+    pub price: f64,
+    /// 均价：从第一个点到当前点按成交量加权的价格，保留 2 位小数。
+    ///
+    /// 响应中没有可用的均价字段（第二个数字在 pytdx 中称为 `reversed1`，含义未知），
+    /// 因此由 `price` 和 `vol` 计算得到，与行情软件以成交额计算的均价可能有细微差别。
+    pub avg_price: f64,
+    /// 成交量，单位：手
+    pub vol: u32,
+}
+
+impl MinuteTimeData {
+    /// 从 `pos` 位置开始解析所有分时点，写入 `data`（会先清空），返回响应信息中的点数。
+    /// 字节不完整时返回 [`Error::ShortFrame`][crate::Error::ShortFrame]。
+    ///
+    /// 每个点由三个 [`price`][crate::tcp::helper::price] 的不定长编码组成：
+    /// 相对上一个点的价格差值、含义未知的数字（跳过）、成交量。
+    /// [`avg_price`][Self::avg_price] 由前两者累计计算。
+    pub fn parse(v: &[u8], mut pos: usize, data: &mut Vec<Self>) -> crate::Result<usize> {
+        use crate::{
+            bytes_helper::u16_from_le_bytes,
+            tcp::helper::{need, price},
+        };
+
+        need(v, pos)?;
+        let count = u16_from_le_bytes(v, 0) as usize;
+        let (mut last_price, mut sum_amount, mut sum_vol) = (0, 0i64, 0i64);
+        data.clear();
+        for i in 0..count {
+            last_price += price(v, &mut pos)?;
+            price(v, &mut pos)?;
+            let vol = price(v, &mut pos)? as u32;
+            sum_amount += last_price as i64 * vol as i64;
+            sum_vol += vol as i64;
+            let avg = if sum_vol == 0 {
+                last_price as f64
+            } else {
+                (sum_amount as f64 / sum_vol as f64).round()
+            };
+            data.push(Self {
+                time: Self::minutes(i),
+                price: last_price as f64 / 100.,
+                avg_price: avg / 100.,
+                vol,
+            });
+        }
+        Ok(count)
+    }
+
+    /// 第 i 个分时点（从 0 开始）对应的分钟数：前 120 个点从 9:31 开始，之后从 13:01 开始。
+    pub fn minutes(i: usize) -> u16 {
+        let i = i as u16;
+        if i < 120 {
+            9 * 60 + 31 + i
+        } else {
+            13 * 60 + 1 + (i - 120)
+        }
+    }
+
+    /// 按照 decimal_point 换算价格和均价。见 [`rescale`][crate::tcp::helper::rescale]。
+    pub fn rescale(&mut self, decimal_point: u8) -> &mut Self {
+        use crate::tcp::helper::rescale;
+        self.price = rescale(self.price, decimal_point);
+        self.avg_price = rescale(self.avg_price, decimal_point);
+        self
+    }
+
+    /// `[时, 分]`
+    pub fn hm_arr(&self) -> [u16; 2] {
+        [self.time / 60, self.time % 60]
+    }
+}
+
+#[test]
+fn minute_new_modify() -> crate::Result<()> {
+    let minute = MinuteTime::new(0, "000001")?;
+    let mut minute2 = MinuteTime::new(1, "600000")?;
+    minute2.market(0).code("000001")?;
+    compare!(MinuteTime::default(), minute, minute2);
+
+    let history = HistoryMinuteTime::new(0, "000001", 20210923)?;
+    let mut history2 = HistoryMinuteTime::new(1, "600000", 20200101)?;
+    history2.market(0).code("000001")?.date(20210923);
+    compare!(HistoryMinuteTime::default(), history, history2);
+    Ok(())
+}
+
+#[test]
+fn minutes() {
+    let hm = |i| {
+        let t = MinuteTimeData::minutes(i);
+        [t / 60, t % 60]
+    };
+    assert_eq!(hm(0), [9, 31]);
+    assert_eq!(hm(119), [11, 30]);
+    assert_eq!(hm(120), [13, 1]);
+    assert_eq!(hm(239), [15, 0]);
+}
+
+#[test]
+fn connection() -> crate::Result<()> {
+    crate::tcp::tests::connection(MinuteTime::default())?;
+    crate::tcp::tests::connection(HistoryMinuteTime::default())
+}
+
+/// 手写的响应字节：只验证解析与编码一致。
+#[test]
+fn parse() {
+    #[rustfmt::skip]
+    let res = [
+        MinuteTimeData { time: 571, price: 17.68, avg_price: 17.68, vol: 12000 },
+        MinuteTimeData { time: 572, price: 17.65, avg_price: 17.67, vol: 3400 },
+        MinuteTimeData { time: 573, price: 17.7, avg_price: 17.68, vol: 2210 },
+        MinuteTimeData { time: 574, price: 17.69, avg_price: 17.68, vol: 980 },
+    ];
+
+    let mut minute = MinuteTime::default();
+    let arr = vec![
+        0x04, 0x00, 0x00, 0x00, 0xa8, 0x1b, 0xa8, 0x1b, 0xa0, 0xbb, 0x01, 0x43, 0x41, 0x88, 0x35,
+        0x05, 0x01, 0xa2, 0x22, 0x41, 0x00, 0x94, 0x0f,
+    ];
+    crate::tcp::tests::truncated(&mut minute, &arr);
+    minute.parse(arr).unwrap();
+    assert_eq!(minute.count, 4);
+    compare!(res, minute.data.as_slice());
+
+    let mut history = HistoryMinuteTime::default();
+    history
+        .parse(vec![
+            0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0xa8, 0x1b, 0xa8, 0x1b, 0xa0, 0xbb, 0x01, 0x43,
+            0x41, 0x88, 0x35, 0x05, 0x01, 0xa2, 0x22, 0x41, 0x00, 0x94, 0x0f,
+        ])
+        .unwrap();
+    assert_eq!(history.count, 4);
+    compare!(res, history.data.as_slice());
+}
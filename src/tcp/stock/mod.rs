@@ -1,5 +1,26 @@
+mod block;
+pub use block::{BlockInfo, BlockInfoMeta, BlockInfoMetaData};
+
+mod company;
+pub use company::{CompanyInfoCategory, CompanyInfoCategoryData, CompanyInfoContent};
+
+mod finance;
+pub use finance::{FinanceInfo, FinanceInfoData};
+
+mod index;
+pub use index::{IndexKline, IndexKlineData};
+
 mod kline;
-pub use kline::{Kline, KlineData};
+pub use kline::{Kline, KlineData, KLINE_MAX_COUNT};
+
+mod minute;
+pub use minute::{HistoryMinuteTime, MinuteTime, MinuteTimeData};
+
+mod quotes;
+pub use quotes::{Quotes, QuotesData};
+
+mod transaction;
+pub use transaction::{HistoryTransaction, Transaction, TransactionData, TRANSACTION_MAX_COUNT};
 
 mod xdxr;
 pub use xdxr::*;
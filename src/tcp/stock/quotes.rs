@@ -0,0 +1,330 @@
+use crate::tcp::{helper::code_bytes, Tdx};
+
+/// 查询股票实时行情。对应于 pytdx 中的 hq.get_security_quotes、GetSecurityQuotesCmd。
+///
+/// 一次请求可以查询多只股票，传入 `(市场, 股票代码)` 的列表即可。
+/// 0 代表深市；1 代表沪市。
+///
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容，请使用 [`Quotes::new`] 构造查询条件。
+#[derive(Debug, Clone)]
+pub struct Quotes<'d> {
+    pub send: Box<[u8]>,
+    pub codes: Vec<(u16, &'d str)>,
+    /// 响应信息中的股票数量。
+    pub count: usize,
+    pub response: Vec<u8>,
+    pub data: Vec<QuotesData>,
+}
+
+/// 默认查询 #sz000001# 的实时行情，与 [`Quotes::SEND`] 一致。
+impl<'d> Default for Quotes<'d> {
+    fn default() -> Self {
+        Self {
+            send: {
+                let mut arr = [0; Self::LEN];
+                arr.copy_from_slice(Self::SEND);
+                arr.into()
+            },
+            codes: vec![(0, "000001")],
+            count: 0,
+            response: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+}
+
+impl<'d> Quotes<'d> {
+    /// 请求字节中，股票列表之前的字节长度。
+    const HEADER_LEN: usize = 22;
+
+    /// 参数为 `(市场, 股票代码)` 的列表。
+    ///
+    /// 当某个 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
+    pub fn new(codes: &[(u16, &'d str)]) -> crate::Result<Self> {
+        let len = codes.len() as u16;
+        let pkg_len = len * 7 + 12;
+        let mut send = Vec::with_capacity(Self::HEADER_LEN + codes.len() * 7);
+        send.extend_from_slice(&Self::SEND[..6]);
+        send.extend_from_slice(&pkg_len.to_le_bytes());
+        send.extend_from_slice(&pkg_len.to_le_bytes());
+        send.extend_from_slice(&Self::SEND[10..20]);
+        send.extend_from_slice(&len.to_le_bytes());
+        for &(market, code) in codes {
+            send.push(market as u8);
+            send.extend_from_slice(&code_bytes(code)?);
+        }
+        Ok(Self {
+            send: send.into(),
+            codes: codes.into(),
+            count: 0,
+            response: Vec::new(),
+            data: Vec::with_capacity(codes.len()),
+        })
+    }
+}
+
+impl<'d> Tdx for Quotes<'d> {
+    type Item = [QuotesData];
+
+    /// #sz000001# 实时行情的请求字节。长度为 29。
+    /// ```python
+    /// struct.pack("<HIHHIIHH", bytes) # 前 22 字节，python 中的解读方式
+    /// struct.pack("<B6s", bytes)      # 之后每只股票 7 字节
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x0c, 0x01, 0x20, 0x63, 0x00, 0x02, 0x13, 0x00, 0x13, 0x00, 0x3e, 0x05, 0x05, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x30, 0x31,
+    ];
+    const TAG: &'static str = "实时行情";
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 2 字节未知，之后 2 字节表示股票数量，剩余字节使用 [`QuotesData::parse`] 依次解析。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        crate::tcp::helper::need(&v, 4)?;
+        let count = crate::bytes_helper::u16_from_le_bytes(&v, 2) as usize;
+        let mut pos = 4;
+        self.data = (0..count)
+            .map(|_| QuotesData::parse(&v, &mut pos))
+            .collect::<crate::Result<_>>()?;
+        self.count = count;
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// [`Quotes`] 的解析结果：单只股票的实时行情。
+///
+/// 价格的单位为元；成交量、挂单量的单位为手；成交额的单位为元。
+#[derive(Debug, Default, Clone, serde::Serialize)]
+pub struct QuotesData {
+    /// 0 代表深市；1 代表沪市。
+    pub market: u8,
+    /// 6 位股票代码
+    pub code: String,
+    /// 现价
+    pub price: f64,
+    /// 昨收
+    pub preclose: f64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    /// 总量
+    pub vol: u32,
+    /// 现量
+    pub cur_vol: u32,
+    /// 成交额
+    pub amount: f64,
+    /// 内盘
+    pub s_vol: u32,
+    /// 外盘
+    pub b_vol: u32,
+    /// 买一至买五的价格
+    pub bid: [f64; 5],
+    /// 卖一至卖五的价格
+    pub ask: [f64; 5],
+    /// 买一至买五的挂单量
+    pub bid_vol: [u32; 5],
+    /// 卖一至卖五的挂单量
+    pub ask_vol: [u32; 5],
+}
+
+impl QuotesData {
+    /// 从 `pos` 位置开始解析一只股票的行情，并把 `pos` 移动到下一只股票的起始位置。
+    /// 字节不完整时返回 [`Error::ShortFrame`][crate::Error::ShortFrame]。
+    ///
+    /// 除了前 9 字节（市场、代码、2 字节未知数据）和成交额以固定长度解析，
+    /// 其余数据均为 [`price`][crate::tcp::helper::price] 的不定长编码：
+    /// 现价为实际数值，昨收、开盘、最高、最低和五档价格都是相对现价的差值。
+    ///
+    /// ```python
+    /// (market, code, active1) = struct.unpack("<B6sH", bytes) # python 表示方式
+    /// ```
+    pub fn parse(v: &[u8], pos: &mut usize) -> crate::Result<Self> {
+        use crate::{
+            bytes_helper::{u32_from_le_bytes, u8_from_le_bytes},
+            tcp::helper::{need, price, vol_amount},
+        };
+
+        let p = *pos;
+        need(v, p + 9)?;
+        let market = u8_from_le_bytes(v, p);
+        let code = String::from_utf8_lossy(&v[p + 1..p + 7]).into_owned();
+        *pos += 9;
+
+        let base = price(v, pos)?;
+        let real = |diff: i32| (base + diff) as f64 / 100.;
+        let preclose = real(price(v, pos)?);
+        let open = real(price(v, pos)?);
+        let high = real(price(v, pos)?);
+        let low = real(price(v, pos)?);
+        price(v, pos)?; // 服务器时间
+        price(v, pos)?; // 未知
+        let vol = price(v, pos)? as u32;
+        let cur_vol = price(v, pos)? as u32;
+        need(v, *pos + 4)?;
+        let amount = vol_amount(u32_from_le_bytes(v, *pos) as i32);
+        *pos += 4;
+        let s_vol = price(v, pos)? as u32;
+        let b_vol = price(v, pos)? as u32;
+        price(v, pos)?; // 未知
+        price(v, pos)?; // 未知
+
+        let (mut bid, mut ask, mut bid_vol, mut ask_vol) = ([0.; 5], [0.; 5], [0; 5], [0; 5]);
+        for i in 0..5 {
+            bid[i] = real(price(v, pos)?);
+            ask[i] = real(price(v, pos)?);
+            bid_vol[i] = price(v, pos)? as u32;
+            ask_vol[i] = price(v, pos)? as u32;
+        }
+
+        need(v, *pos + 2)?;
+        *pos += 2; // 未知
+        for _ in 0..4 {
+            price(v, pos)?; // 未知
+        }
+        need(v, *pos + 4)?;
+        *pos += 4; // 未知
+
+        Ok(Self {
+            market,
+            code,
+            price: real(0),
+            preclose,
+            open,
+            high,
+            low,
+            vol,
+            cur_vol,
+            amount,
+            s_vol,
+            b_vol,
+            bid,
+            ask,
+            bid_vol,
+            ask_vol,
+        })
+    }
+
+    /// 按照 decimal_point 换算所有价格，成交额不变。见 [`rescale`][crate::tcp::helper::rescale]。
+    pub fn rescale(&mut self, decimal_point: u8) -> &mut Self {
+        use crate::tcp::helper::rescale;
+        for p in [
+            &mut self.price,
+            &mut self.preclose,
+            &mut self.open,
+            &mut self.high,
+            &mut self.low,
+        ]
+        .into_iter()
+        .chain(&mut self.bid)
+        .chain(&mut self.ask)
+        {
+            *p = rescale(*p, decimal_point);
+        }
+        self
+    }
+}
+
+#[test]
+fn quotes_new() -> crate::Result<()> {
+    let quotes = Quotes::new(&[(0, "000001")])?;
+    compare!(Quotes::default(), quotes);
+
+    let quotes = Quotes::new(&[(0, "000001"), (1, "600300")])?;
+    assert_eq!(quotes.send.len(), 36);
+    assert_eq!(&quotes.send[6..10], &[0x1a, 0x00, 0x1a, 0x00]);
+    assert_eq!(&quotes.send[20..22], &[0x02, 0x00]);
+    assert_eq!(
+        &quotes.send[29..],
+        &[0x01, 0x36, 0x30, 0x30, 0x33, 0x30, 0x30]
+    );
+
+    let err = Quotes::new(&[(0, "000001"), (1, "60030")]).unwrap_err();
+    assert!(
+        matches!(err, crate::Error::InvalidCode(ref c) if c == "60030"),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn connection() -> crate::Result<()> {
+    crate::tcp::tests::connection(Quotes::default())
+}
+
+/// 手写的响应字节：只验证解析与编码一致。
+///
+/// 成交额约等于 `vol * 100 * 均价`。
+#[test]
+fn parse() {
+    let mut quotes = Quotes::new(&[(0, "000001"), (1, "600300")]).unwrap();
+    let arr = vec![
+        0xb1, 0xcb, 0x02, 0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x30, 0x31, 0x95, 0x0a, 0xa8, 0x1b,
+        0x07, 0x48, 0x00, 0x62, 0x8e, 0x84, 0xa7, 0x0e, 0xe8, 0x1b, 0xb0, 0xc5, 0x01, 0x22, 0xd0,
+        0xf9, 0xa8, 0x4b, 0xb0, 0x62, 0x80, 0x63, 0x00, 0x1b, 0x41, 0x00, 0xb0, 0x12, 0xb6, 0x04,
+        0x42, 0x01, 0x90, 0x01, 0x2d, 0x43, 0x02, 0xa4, 0x05, 0x07, 0x44, 0x03, 0x18, 0x94, 0x0f,
+        0x45, 0x04, 0x86, 0x1f, 0x82, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x95,
+        0x0a, 0x01, 0x36, 0x30, 0x30, 0x33, 0x30, 0x30, 0x95, 0x0a, 0xb1, 0x04, 0x42, 0x00, 0x06,
+        0x43, 0x8e, 0x84, 0xa7, 0x0e, 0xf1, 0x04, 0xa2, 0xa0, 0x06, 0x0c, 0x82, 0x70, 0x6e, 0x4b,
+        0xa8, 0x86, 0x03, 0xba, 0x99, 0x03, 0x00, 0x1b, 0x41, 0x00, 0x05, 0x0a, 0x42, 0x01, 0x06,
+        0x0b, 0x43, 0x02, 0x07, 0x0c, 0x44, 0x03, 0x08, 0x0d, 0x45, 0x04, 0x09, 0x0e, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x95, 0x0a,
+    ];
+    let res = [
+        QuotesData {
+            market: 0,
+            code: "000001".into(),
+            price: 17.68,
+            preclose: 17.75,
+            open: 17.6,
+            high: 17.68,
+            low: 17.34,
+            vol: 12656,
+            cur_vol: 34,
+            amount: 22148000.0,
+            s_vol: 6320,
+            b_vol: 6336,
+            bid: [17.67, 17.66, 17.65, 17.64, 17.63],
+            ask: [17.68, 17.69, 17.7, 17.71, 17.72],
+            bid_vol: [1200, 80, 356, 24, 1990],
+            ask_vol: [310, 45, 7, 980, 66],
+        },
+        QuotesData {
+            market: 1,
+            code: "600300".into(),
+            price: 3.05,
+            preclose: 3.03,
+            open: 3.05,
+            high: 3.11,
+            low: 3.02,
+            vol: 51234,
+            cur_vol: 12,
+            amount: 15626370.0,
+            s_vol: 25000,
+            b_vol: 26234,
+            bid: [3.04, 3.03, 3.02, 3.01, 3.0],
+            ask: [3.05, 3.06, 3.07, 3.08, 3.09],
+            bid_vol: [5, 6, 7, 8, 9],
+            ask_vol: [10, 11, 12, 13, 14],
+        },
+    ];
+    crate::tcp::tests::truncated(&mut quotes, &arr);
+    quotes.parse(arr).unwrap();
+    assert_eq!(quotes.count, 2);
+    compare!(res, quotes.data.as_slice());
+
+    // 同样的字节如果属于 3 位小数的证券，价格应为 0.305 等
+    let mut etf = quotes.data[1].clone();
+    etf.rescale(3);
+    assert_eq!([etf.price, etf.preclose, etf.low], [0.305, 0.303, 0.302]);
+    assert_eq!([etf.bid[0], etf.ask[4]], [0.304, 0.309]);
+    assert_eq!(etf.amount, 15626370.0);
+}
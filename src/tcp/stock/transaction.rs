@@ -0,0 +1,425 @@
+use crate::tcp::{helper::code_bytes, Tcp, Tdx};
+
+/// 单次请求最多返回的逐笔成交数量。
+pub const TRANSACTION_MAX_COUNT: u16 = 2000;
+
+/// 查询股票当日逐笔成交。对应于 pytdx 中的 hq.get_transaction_data、GetTransactionData。
+///
+/// start 表示从最新一笔成交往前数的位置（0 表示最新），count 最大为
+/// [`TRANSACTION_MAX_COUNT`]。获取全天的数据请使用 [`Transaction::recv_all`]。
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct Transaction<'d> {
+    pub send: Box<[u8]>,
+    pub market: u16,
+    pub code: &'d str,
+    pub start: u16,
+    pub count: u16,
+    pub response: Vec<u8>,
+    pub data: Vec<TransactionData>,
+}
+
+/// 为了对应 [`Transaction::SEND`] 的含义，count 默认为 10。
+#[rustfmt::skip]
+impl<'d> Default for Transaction<'d> {
+    fn default() -> Self {
+        Self { market: 0, code: "000001", start: 0, count: 10,
+               send:     { let mut v = [0; Self::LEN]; v.copy_from_slice(Self::SEND); v.into() },
+               response: Vec::new(),
+               data:     Vec::new(), }
+    }
+}
+
+impl<'d> Transaction<'d> {
+    /// 0 代表深市；1 代表沪市。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
+    pub fn new(market: u16, code: &'d str, start: u16, count: u16) -> crate::Result<Self> {
+        let mut t = Self::default();
+        t.market(market).code(code)?.start(start).count(count);
+        Ok(t)
+    }
+
+    /// 修改市场。
+    pub fn market(&mut self, market: u16) -> &mut Self {
+        self.market = market;
+        self.send[12..14].copy_from_slice(&market.to_le_bytes());
+        self
+    }
+
+    /// 修改股票。当代码不正确时，不能正常得到响应。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        self.send[14..20].copy_from_slice(&code_bytes(code)?);
+        self.code = code;
+        Ok(self)
+    }
+
+    /// 修改起始位置。
+    pub fn start(&mut self, start: u16) -> &mut Self {
+        self.start = start;
+        self.send[20..22].copy_from_slice(&start.to_le_bytes());
+        self
+    }
+
+    /// 修改查询数量。
+    pub fn count(&mut self, count: u16) -> &mut Self {
+        self.count = count;
+        self.send[22..24].copy_from_slice(&count.to_le_bytes());
+        self
+    }
+
+    /// 从最新一笔开始，不断增大 start 来翻页，直到取得当日所有逐笔成交。
+    ///
+    /// 返回的数据按时间顺序排列。调用之后 start 和 count 被修改。
+    ///
+    /// start 为 u16，因此翻页的起始位置不能超过 65535：当日成交笔数超出这个范围时，
+    /// 返回 [`Error::Custom`][crate::Error::Custom]，而不是返回不完整的数据。
+    pub fn recv_all(&mut self, tcp: &mut Tcp) -> crate::Result<Vec<TransactionData>> {
+        recv_all(self, tcp, |t, start| {
+            t.start(start).count(TRANSACTION_MAX_COUNT);
+        })
+    }
+}
+
+impl<'d> Tdx for Transaction<'d> {
+    type Item = [TransactionData];
+
+    /// #sz000001# 最新 10 笔成交的请求字节。长度为 24。
+    /// ```python
+    /// struct.pack("<H6sHH", bytes) # 后 12 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x0c, 0x17, 0x08, 0x01, 0x01, 0x01, 0x0e, 0x00, 0x0e, 0x00, 0xc5, 0x0f, 0x00, 0x00, 0x30,
+        0x30, 0x30, 0x30, 0x30, 0x31, 0x00, 0x00, 0x0a, 0x00,
+    ];
+    const TAG: &'static str = "逐笔成交";
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 2 字节表示成交笔数，之后使用 [`TransactionData::parse`] 依次解析。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        TransactionData::parse(&v, 2, false, &mut self.data)?;
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// 查询股票历史某日的逐笔成交。对应于 pytdx 中的 hq.get_history_transaction_data、
+/// GetHistoryTransactionData。
+///
+/// 参数含义与 [`Transaction`] 相同，另需指定日期。获取全天的数据请使用
+/// [`HistoryTransaction::recv_all`]。
+/// ## 注意
+/// 只修改字段并不会更改待发送字节的内容。
+/// 如果你需要修改查询条件，请使用相应的方法。
+#[derive(Debug, Clone)]
+pub struct HistoryTransaction<'d> {
+    pub send: Box<[u8]>,
+    pub market: u16,
+    pub code: &'d str,
+    /// 年月日，比如 `20210923`
+    pub date: u32,
+    pub start: u16,
+    pub count: u16,
+    pub response: Vec<u8>,
+    pub data: Vec<TransactionData>,
+}
+
+/// 为了对应 [`HistoryTransaction::SEND`] 的含义，count 默认为 10。
+#[rustfmt::skip]
+impl<'d> Default for HistoryTransaction<'d> {
+    fn default() -> Self {
+        Self { market: 0, code: "000001", date: 20210923, start: 0, count: 10,
+               send:     { let mut v = [0; Self::LEN]; v.copy_from_slice(Self::SEND); v.into() },
+               response: Vec::new(),
+               data:     Vec::new(), }
+    }
+}
+
+impl<'d> HistoryTransaction<'d> {
+    /// 0 代表深市；1 代表沪市；date 为年月日，比如 `20210923`。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]。
+    pub fn new(
+        market: u16,
+        code: &'d str,
+        date: u32,
+        start: u16,
+        count: u16,
+    ) -> crate::Result<Self> {
+        let mut t = Self::default();
+        t.market(market)
+            .code(code)?
+            .date(date)
+            .start(start)
+            .count(count);
+        Ok(t)
+    }
+
+    /// 修改日期。
+    pub fn date(&mut self, date: u32) -> &mut Self {
+        self.date = date;
+        self.send[12..16].copy_from_slice(&date.to_le_bytes());
+        self
+    }
+
+    /// 修改市场。
+    pub fn market(&mut self, market: u16) -> &mut Self {
+        self.market = market;
+        self.send[16..18].copy_from_slice(&market.to_le_bytes());
+        self
+    }
+
+    /// 修改股票。当代码不正确时，不能正常得到响应。
+    ///
+    /// 当 code 的字节长度不是 6 时，返回 [`Error::InvalidCode`][crate::Error::InvalidCode]，
+    /// 且不修改任何内容。
+    pub fn code(&mut self, code: &'d str) -> crate::Result<&mut Self> {
+        self.send[18..24].copy_from_slice(&code_bytes(code)?);
+        self.code = code;
+        Ok(self)
+    }
+
+    /// 修改起始位置。
+    pub fn start(&mut self, start: u16) -> &mut Self {
+        self.start = start;
+        self.send[24..26].copy_from_slice(&start.to_le_bytes());
+        self
+    }
+
+    /// 修改查询数量。
+    pub fn count(&mut self, count: u16) -> &mut Self {
+        self.count = count;
+        self.send[26..28].copy_from_slice(&count.to_le_bytes());
+        self
+    }
+
+    /// 从最后一笔开始，不断增大 start 来翻页，直到取得当日所有逐笔成交。
+    ///
+    /// 返回的数据按时间顺序排列。调用之后 start 和 count 被修改。
+    ///
+    /// start 为 u16，因此翻页的起始位置不能超过 65535：当日成交笔数超出这个范围时，
+    /// 返回 [`Error::Custom`][crate::Error::Custom]，而不是返回不完整的数据。
+    pub fn recv_all(&mut self, tcp: &mut Tcp) -> crate::Result<Vec<TransactionData>> {
+        recv_all(self, tcp, |t, start| {
+            t.start(start).count(TRANSACTION_MAX_COUNT);
+        })
+    }
+}
+
+impl<'d> Tdx for HistoryTransaction<'d> {
+    type Item = [TransactionData];
+
+    /// #sz000001# 在 2021-09-23 最后 10 笔成交的请求字节。长度为 28。
+    /// ```python
+    /// struct.pack("<IH6sHH", bytes) # 后 16 字节，python 中的解读方式
+    /// ```
+    const SEND: &'static [u8] = &[
+        0x0c, 0x01, 0x30, 0x01, 0x00, 0x01, 0x12, 0x00, 0x12, 0x00, 0xb5, 0x0f, 0xeb, 0x64, 0x34,
+        0x01, 0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x30, 0x31, 0x00, 0x00, 0x0a, 0x00,
+    ];
+    const TAG: &'static str = "历史逐笔成交";
+
+    fn send(&mut self) -> &[u8] {
+        &self.send
+    }
+
+    /// 前 2 字节表示成交笔数，跳过 4 字节未知数据之后使用 [`TransactionData::parse`] 解析。
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        TransactionData::parse(&v, 6, true, &mut self.data)?;
+        self.response = v;
+        Ok(())
+    }
+
+    fn result(&self) -> &Self::Item {
+        &self.data
+    }
+}
+
+/// 翻页获取逐笔成交：每页的数据都在前一页之前，所以最后把所有页倒序拼接起来。
+/// 当某一页的数量少于请求数量时，说明已经到达当日第一笔成交。
+fn recv_all<T>(
+    tdx: &mut T,
+    tcp: &mut Tcp,
+    page: impl Fn(&mut T, u16),
+) -> crate::Result<Vec<TransactionData>>
+where
+    T: Tdx<Item = [TransactionData]>,
+{
+    let mut pages = Vec::new();
+    let mut start = 0u16;
+    loop {
+        page(tdx, start);
+        let data = tdx.recv_parsed(tcp)?;
+        let len = data.len();
+        pages.push(data.to_vec());
+        if len < TRANSACTION_MAX_COUNT as usize {
+            break;
+        }
+        start = start.checked_add(len as u16).ok_or(crate::Error::Custom(
+            "逐笔成交的 start 超出 u16 范围，无法继续翻页",
+        ))?;
+    }
+    Ok(pages.into_iter().rev().flatten().collect())
+}
+
+/// [`Transaction`] 和 [`HistoryTransaction`] 的解析结果：一笔成交。
+#[derive(Debug, Default, Clone, serde::Serialize)]
+pub struct TransactionData {
+    /// 分钟数，即从 0 点开始的分钟数，比如 `896` 表示 14:56。
+    pub time: u16,
+    pub price: f64,
+    /// 成交量，单位：手
+    pub vol: u32,
+    /// 成交单数。历史逐笔成交不含此数据，为 0。
+    pub num: u32,
+    /// 0 表示买；1 表示卖；2 表示中性盘（比如集合竞价）。
+    pub buyorsell: u32,
+}
+
+impl TransactionData {
+    /// 从 `pos` 位置开始解析所有成交，写入 `data`（会先清空），返回响应信息中的成交笔数。
+    /// 字节不完整时返回 [`Error::ShortFrame`][crate::Error::ShortFrame]。
+    ///
+    /// 每笔成交先以 2 字节表示分钟数，之后为 [`price`][crate::tcp::helper::price]
+    /// 的不定长编码：价格差值（相对上一笔）、成交量、成交单数（`history` 为 true 时没有）、
+    /// 买卖方向和 1 个未知数据。
+    pub fn parse(
+        v: &[u8],
+        mut pos: usize,
+        history: bool,
+        data: &mut Vec<Self>,
+    ) -> crate::Result<usize> {
+        use crate::{
+            bytes_helper::u16_from_le_bytes,
+            tcp::helper::{need, price},
+        };
+
+        need(v, 2)?;
+        let count = u16_from_le_bytes(v, 0) as usize;
+        let mut last_price = 0;
+        data.clear();
+        data.reserve(count);
+        for _ in 0..count {
+            need(v, pos + 2)?;
+            let time = u16_from_le_bytes(v, pos);
+            pos += 2;
+            last_price += price(v, &mut pos)?;
+            let vol = price(v, &mut pos)? as u32;
+            let num = if history {
+                0
+            } else {
+                price(v, &mut pos)? as u32
+            };
+            let buyorsell = price(v, &mut pos)? as u32;
+            price(v, &mut pos)?; // 未知
+            data.push(Self {
+                time,
+                price: last_price as f64 / 100.,
+                vol,
+                num,
+                buyorsell,
+            });
+        }
+        Ok(count)
+    }
+
+    /// 按照 decimal_point 换算价格。见 [`rescale`][crate::tcp::helper::rescale]。
+    pub fn rescale(&mut self, decimal_point: u8) -> &mut Self {
+        self.price = crate::tcp::helper::rescale(self.price, decimal_point);
+        self
+    }
+
+    /// `[时, 分]`
+    pub fn hm_arr(&self) -> [u16; 2] {
+        [self.time / 60, self.time % 60]
+    }
+}
+
+#[test]
+fn transaction_new_modify() -> crate::Result<()> {
+    let t = Transaction::new(0, "000001", 0, 10)?;
+    let mut t2 = Transaction::new(1, "600000", 100, 2000)?;
+    t2.market(0).code("000001")?.start(0).count(10);
+    compare!(Transaction::default(), t, t2);
+
+    let h = HistoryTransaction::new(0, "000001", 20210923, 0, 10)?;
+    let mut h2 = HistoryTransaction::new(1, "600000", 20200101, 100, 2000)?;
+    h2.market(0)
+        .code("000001")?
+        .date(20210923)
+        .start(0)
+        .count(10);
+    compare!(HistoryTransaction::default(), h, h2);
+    Ok(())
+}
+
+#[test]
+fn connection() -> crate::Result<()> {
+    crate::tcp::tests::connection(Transaction::default())?;
+    crate::tcp::tests::connection(HistoryTransaction::default())
+}
+
+/// 手写的响应字节：只验证解析与编码一致。
+#[test]
+fn parse() {
+    #[rustfmt::skip]
+    let mut res = [
+        TransactionData { time: 896, price: 17.68, vol: 120, num: 5, buyorsell: 0 },
+        TransactionData { time: 896, price: 17.67, vol: 30, num: 2, buyorsell: 1 },
+        TransactionData { time: 897, price: 17.69, vol: 1000, num: 21, buyorsell: 0 },
+        TransactionData { time: 900, price: 17.69, vol: 4500, num: 88, buyorsell: 2 },
+    ];
+
+    let mut t = Transaction::default();
+    let arr = vec![
+        0x04, 0x00, 0x80, 0x03, 0xa8, 0x1b, 0xb8, 0x01, 0x05, 0x00, 0x00, 0x80, 0x03, 0x41, 0x1e,
+        0x02, 0x01, 0x00, 0x81, 0x03, 0x02, 0xa8, 0x0f, 0x15, 0x00, 0x00, 0x84, 0x03, 0x00, 0x94,
+        0x46, 0x98, 0x01, 0x02, 0x00,
+    ];
+    crate::tcp::tests::truncated(&mut t, &arr);
+    t.parse(arr).unwrap();
+    compare!(res, t.data.as_slice());
+
+    res.iter_mut().for_each(|d| d.num = 0);
+    let mut h = HistoryTransaction::default();
+    h.parse(vec![
+        0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x03, 0xa8, 0x1b, 0xb8, 0x01, 0x00, 0x00, 0x80,
+        0x03, 0x41, 0x1e, 0x01, 0x00, 0x81, 0x03, 0x02, 0xa8, 0x0f, 0x00, 0x00, 0x84, 0x03, 0x00,
+        0x94, 0x46, 0x02, 0x00,
+    ])
+    .unwrap();
+    compare!(res, h.data.as_slice());
+}
+
+/// 模拟服务器对每一页都返回 [`TRANSACTION_MAX_COUNT`] 笔成交：翻页至 start 溢出时返回错误。
+#[test]
+fn recv_all_overflow() -> crate::Result<()> {
+    use crate::tcp::{mock::Fixture, tests::MockServer};
+
+    let mut page = TRANSACTION_MAX_COUNT.to_le_bytes().to_vec();
+    for _ in 0..TRANSACTION_MAX_COUNT {
+        // 时间 9:30，价格差值 0，成交量 1，单数 1，买，未知
+        page.extend_from_slice(&[0x3a, 0x02, 0x00, 0x01, 0x01, 0x00, 0x00]);
+    }
+    let mut fixture = Fixture::handshake();
+    fixture.fallback_data(&page);
+    let server = MockServer::start(fixture)?;
+    let mut tcp = Tcp::new_with_ip(&server.addr)?;
+    let err = Transaction::default().recv_all(&mut tcp).unwrap_err();
+    assert!(matches!(err, crate::Error::Custom(_)), "{err}");
+    // 三个测试包，以及 start 为 0, 2000, ..., 64000 的 33 页
+    assert_eq!(server.requests(), 3 + 33);
+    Ok(())
+}
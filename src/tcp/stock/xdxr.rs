@@ -42,11 +42,11 @@ impl<'a> Tdx for Xdxr<'a> {
         &self.send
     }
 
-    fn parse(&mut self, v: Vec<u8>) {
-        if v.len() < 11 {
-            return;
-        }
+    fn parse(&mut self, v: Vec<u8>) -> crate::Result<()> {
+        use crate::tcp::helper::need;
+        need(&v, 11)?;
         let count = crate::bytes_helper::u16_from_le_bytes(&v, 9) as usize;
+        need(&v, 11 + count * 29)?;
         let old = self.count.replace(count);
         if old.is_some() {
             self.data.resize_with(count, XdxrData::default);
@@ -64,6 +64,7 @@ impl<'a> Tdx for Xdxr<'a> {
                 .last();
         };
         self.response = v;
+        Ok(())
     }
 
     fn result(&self) -> &Self::Item {
@@ -122,7 +123,7 @@ impl XdxrData {
             }
         }
         let market = u8_from_le_bytes(bytes, 0);
-        let code = unsafe { std::str::from_utf8_unchecked(&bytes[1..7]) }.into();
+        let code = String::from_utf8_lossy(&bytes[1..7]).into_owned();
         let date = crate::tcp::helper::datetime(&bytes[8..12], 9).to_u32();
         let category = u8_from_le_bytes(bytes, 12);
         let (fh_qltp, pgj_qzgb, sg_hltp, pg_hzgb) = match category {
@@ -153,7 +154,7 @@ impl XdxrData {
 }
 
 #[test]
-fn connection() -> std::io::Result<()> {
+fn connection() -> crate::Result<()> {
     crate::tcp::tests::connection(Xdxr::default())
 }
 
@@ -13,12 +13,43 @@ macro_rules! compare {
 
 use super::{Result, Tcp, Tdx};
 
+/// 测试所用的连接：
+/// - 默认连接真实服务器；
+/// - 环境变量 `RUSTDX_MOCK` 为 fixture 文件路径时，连接回放此文件的 [`MockServer`]，无需网络；
+/// - 环境变量 `RUSTDX_RECORD` 为 fixture 文件路径时，通过 [`Recorder`][super::mock::Recorder]
+///   连接真实服务器，并把记录合并到此文件。
+///
+/// 比如 `RUSTDX_RECORD=$PWD/assets/connection.fixture cargo test connection`
+/// 记录之后，`RUSTDX_MOCK=$PWD/assets/connection.fixture cargo test connection` 可以离线运行。
+pub fn with_tcp<R>(f: impl FnOnce(&mut Tcp) -> Result<R>) -> Result<R> {
+    use super::mock::{Fixture, Recorder};
+    if let Ok(path) = std::env::var("RUSTDX_MOCK") {
+        let server = MockServer::start(Fixture::load(path)?)?;
+        return f(&mut Tcp::new_with_ip(&server.addr)?);
+    }
+    if let Ok(path) = std::env::var("RUSTDX_RECORD") {
+        // 测试是并行的，合并和写入文件时需要加锁
+        static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        let recorder = Recorder::start(super::ip::STOCK_IP[0])?;
+        let res = f(&mut Tcp::new_with_ip(&recorder.addr)?);
+        let _guard = LOCK.lock().unwrap();
+        let mut fixture = Fixture::load(&path).unwrap_or_default();
+        fixture.merge(recorder.finish());
+        fixture.save(&path)?;
+        return res;
+    }
+    f(&mut Tcp::new()?)
+}
+
 pub fn connection<T: Tdx>(mut tdx: T) -> Result<()>
 where
     <T as Tdx>::Item: std::fmt::Debug,
 {
     println!("send: {:?}", tdx.send());
-    println!("recv: {:?}", tdx.recv_parsed(&mut Tcp::new()?)?);
+    println!(
+        "recv: {:?}",
+        with_tcp(|tcp| tdx.recv_parsed(tcp).map(|r| format!("{r:?}")))?
+    );
     Ok(())
 }
 
@@ -28,7 +59,32 @@ where
     <T as Tdx>::Item: std::fmt::Debug,
 {
     println!("send: {:?}", tdx.send());
-    let res = tdx.recv_parsed(&mut Tcp::new()?)?;
-    println!("recv: {res:?}");
+    let res = with_tcp(|tcp| tdx.recv_parsed(tcp).map(|r| format!("{r:?}")))?;
+    println!("recv: {res}");
     Ok(())
 }
+
+pub use super::mock::MockServer;
+
+/// [`count_server`] 对所有请求返回的有效数据：u16 类型的数字。
+pub const COUNT: u16 = 10000;
+
+/// 本地回环地址上的模拟服务器：对任何请求都在 delay 之后返回 [`COUNT`]。
+pub fn count_server(delay: std::time::Duration) -> Result<MockServer> {
+    let mut fixture = super::mock::Fixture::default();
+    fixture.fallback_data(&COUNT.to_le_bytes());
+    MockServer::start_delayed(fixture, delay)
+}
+
+/// 依次解析 response 的每一个真前缀（模拟不完整的响应）：必须返回错误，而不是 panic。
+pub fn truncated<T: Tdx>(tdx: &mut T, response: &[u8]) {
+    for n in 0..response.len() {
+        let res = tdx.parse(response[..n].to_vec());
+        assert!(
+            res.is_err(),
+            "{} 解析 {} 字节的前缀时没有返回错误",
+            T::TAG,
+            n
+        );
+    }
+}
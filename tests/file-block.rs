@@ -0,0 +1,22 @@
+use insta::assert_debug_snapshot;
+use rustdx::file::block::{BlockData, BlockFile};
+use std::collections::BTreeMap;
+
+#[test]
+fn block_gn() -> rustdx::Result<()> {
+    let blocks = BlockData::from_file("assets/block_gn.dat")?;
+    assert_debug_snapshot!("block-gn", blocks);
+
+    let from_dir = BlockFile::Gn.read("assets")?;
+    assert_eq!(format!("{blocks:?}"), format!("{from_dir:?}"));
+
+    let code_blocks: BTreeMap<_, _> = BlockData::code_hashmap(&blocks).into_iter().collect();
+    assert_debug_snapshot!("block-gn-code", code_blocks);
+
+    let block_codes = BlockData::block_hashmap(blocks);
+    assert_eq!(
+        block_codes["白酒"],
+        ["600519", "000858", "000568", "600809"]
+    );
+    Ok(())
+}
@@ -71,3 +71,26 @@ fn write_to_csv(day: Vec<impl Serialize>) -> Result<String> {
     let data = String::from_utf8(wtr.into_inner()?)?;
     Ok(data)
 }
+
+#[test]
+fn day_to_bytes() -> Result<()> {
+    use rustdx::file::day::Day as DayRaw;
+    let path = "assets/sz000001.day";
+    let bytes = std::fs::read(path)?;
+    let days = DayRaw::from_file_into_vec(1, path)?;
+    let round_trip: Vec<u8> = days.iter().flat_map(DayRaw::to_bytes).collect();
+    assert!(round_trip == bytes, "to_bytes 与原文件不一致");
+
+    // 分两次追加到新文件，与原文件完全一致
+    let out = std::env::temp_dir().join(format!("rustdx-day-{}.day", std::process::id()));
+    let _ = std::fs::remove_file(&out);
+    let (a, b) = days.split_at(days.len() / 2);
+    DayRaw::append_to_file(a, &out)?;
+    DayRaw::append_to_file(b, &out)?;
+    assert!(
+        std::fs::read(&out)? == bytes,
+        "append_to_file 与原文件不一致"
+    );
+    std::fs::remove_file(out)?;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use rustdx::file::gbbq::{encrypt, parse, Gbbq, Gbbqs};
+
+#[test]
+fn encrypt_parse() -> rustdx::Result<()> {
+    let src = std::fs::read("assets/gbbq")?;
+    for chunk in src[4..].chunks_exact(29) {
+        let mut bytes = chunk.to_vec();
+        parse(&mut bytes);
+        assert_eq!(encrypt(&mut bytes), chunk);
+    }
+    Ok(())
+}
+
+#[test]
+fn gbbqs_to_bytes() -> rustdx::Result<()> {
+    let src = std::fs::read("assets/gbbq")?;
+    let mut gbbqs = Gbbqs::from_file("assets/gbbq")?;
+    assert!(gbbqs.to_bytes() == src, "未解密时 to_bytes 与原文件不一致");
+
+    let records = gbbqs.to_vec();
+    let count = records.len();
+    let rebuilt = Gbbqs::from_records(records);
+    assert_eq!(rebuilt.count, count);
+    assert!(rebuilt.to_bytes() == src, "from_records 与原文件不一致");
+    assert!(gbbqs.to_bytes() == src, "解密后 to_bytes 与原文件不一致");
+    Ok(())
+}
+
+#[test]
+fn gbbqs_merge_xdxr() -> rustdx::Result<()> {
+    use rustdx::tcp::stock::XdxrData;
+    let xdxr = XdxrData {
+        market: 0,
+        code: "000001".into(),
+        date: 20990701,
+        category: 1,
+        fh_qltp: 2.5,
+        ..Default::default()
+    };
+
+    let mut gbbqs = Gbbqs::from_file("assets/gbbq")?;
+    let mut records = gbbqs.to_vec();
+    let count = records.len();
+    let pos = records.partition_point(|g| (g.code, g.date) <= (&xdxr.code, xdxr.date));
+    records.insert(pos, Gbbq::from(&xdxr));
+    let merged = Gbbqs::from_records(records);
+
+    let path = std::env::temp_dir().join(format!("rustdx-gbbq-{}", std::process::id()));
+    merged.to_file(&path)?;
+    let mut bytes = std::fs::read(&path)?;
+    std::fs::remove_file(path)?;
+    assert_eq!(merged.count, count + 1);
+    assert_eq!(
+        u32::from_le_bytes(bytes[..4].try_into().unwrap()),
+        count as u32 + 1
+    );
+
+    let stock_gbbq = Gbbq::filter_hashmap(Gbbq::iter(&mut bytes[4..]));
+    let last = stock_gbbq[&1].last().unwrap();
+    assert_eq!((last.date, last.category, last.fh_qltp), (20990701, 1, 2.5));
+    Ok(())
+}
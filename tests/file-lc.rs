@@ -0,0 +1,194 @@
+use rustdx::file::lc::{read_dir, Format, Lc, LcFile, Period};
+use std::{fs, path::PathBuf};
+
+/// 2021-08-10 的一根分钟 K 线：minutes 为从 0 点开始的分钟数。
+fn lc_bytes(minutes: u16, close: f32) -> Vec<u8> {
+    let date: u16 = (2021 - 2004) * 2048 + 810;
+    let mut arr = Vec::with_capacity(32);
+    arr.extend_from_slice(&date.to_le_bytes());
+    arr.extend_from_slice(&minutes.to_le_bytes());
+    for price in [10.0f32, 10.5, 9.5, close, 12345.0] {
+        arr.extend_from_slice(&price.to_le_bytes());
+    }
+    arr.extend_from_slice(&100u32.to_le_bytes());
+    arr.extend_from_slice(&[0; 4]);
+    arr
+}
+
+fn vipdoc() -> std::io::Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("rustdx-file-lc-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    for sub in ["sh/minline", "sh/fzline", "sz/fzline"] {
+        fs::create_dir_all(dir.join(sub))?;
+    }
+    fs::write(
+        dir.join("sh/minline/sh600000.lc1"),
+        [lc_bytes(571, 10.1), lc_bytes(572, 10.2)].concat(),
+    )?;
+    fs::write(dir.join("sh/fzline/sh600000.lc5"), lc_bytes(575, 10.3))?;
+    fs::write(dir.join("sz/fzline/sz000001.lc5"), lc_bytes(575, 10.4))?;
+    // 以下文件不符合命名要求，应被忽略
+    fs::write(dir.join("sz/fzline/sz000001.day"), lc_bytes(575, 0.))?;
+    fs::write(dir.join("sz/fzline/szabcdef.lc5"), lc_bytes(575, 0.))?;
+    Ok(dir)
+}
+
+#[test]
+fn lc_dir() -> rustdx::Result<()> {
+    let dir = vipdoc()?;
+    let files = read_dir(&dir)?;
+    let found: Vec<_> = files
+        .iter()
+        .map(|f| (f.symbol(), f.period, f.period.minutes()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("sh600000".into(), Period::Min5, 5),
+            ("sh600000".into(), Period::Min1, 1),
+            ("sz000001".into(), Period::Min5, 5),
+        ]
+    );
+    assert_eq!(read_dir(dir.join("sh/minline"))?.len(), 1);
+
+    let filtered = |ex, code| files.iter().filter(|f| f.matches(ex, code)).count();
+    assert_eq!(filtered(None, None), 3);
+    assert_eq!(filtered(Some("auto"), None), 3);
+    assert_eq!(filtered(Some("sh"), None), 2);
+    assert_eq!(filtered(Some("sz"), Some("000")), 1);
+    assert_eq!(filtered(Some("sh"), Some("000")), 0);
+
+    let rows = files[1].rows()?.collect::<rustdx::Result<Vec<_>>>()?;
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].datetime, "2021-08-10 09:31");
+    assert_eq!(rows[1].datetime, "2021-08-10 09:32");
+    assert_eq!(
+        (rows[0].code.as_str(), rows[0].exchange.as_str()),
+        ("600000", "sh")
+    );
+    assert_eq!((rows[0].close, rows[1].close), (10.1, 10.2));
+    assert_eq!((rows[0].amount, rows[0].vol), (12345.0, 100));
+
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn lc_file_name() {
+    assert!(LcFile::from_path("sh600000.lc1").is_some());
+    assert!(LcFile::from_path("bj430047.lc5").is_some());
+    assert!(LcFile::from_path("sh600000.1").is_some());
+    assert!(LcFile::from_path("sh600000.lc2").is_none());
+    assert!(LcFile::from_path("sh60000.lc1").is_none());
+    assert!(LcFile::from_path("600000sh.lc1").is_none());
+    assert_eq!(
+        Period::from_extension("lc5").map(Period::extension),
+        Some("lc5")
+    );
+}
+
+/// `assets/sz000001.5` 为旧格式的 5 分钟线：价格以 u32 存储。
+#[test]
+fn legacy() -> rustdx::Result<()> {
+    let bytes = fs::read("assets/sz000001.5")?;
+    assert_eq!(Format::detect("5", &bytes), Format::Integer);
+    // 扩展名为 lc5，但价格明显按 u32 存储
+    assert_eq!(Format::detect("lc5", &bytes), Format::Integer);
+    // 同样的 K 线以 f32 存储时
+    let float: Vec<u8> = Lc::parse(1, &bytes, Format::Integer)
+        .iter()
+        .flat_map(|lc| {
+            let mut b = bytes[..32].to_vec();
+            for (pos, p) in [lc.open, lc.high, lc.low, lc.close].into_iter().enumerate() {
+                b[4 + pos * 4..8 + pos * 4].copy_from_slice(&p.to_le_bytes());
+            }
+            b
+        })
+        .collect();
+    assert_eq!(Format::detect("5", &float), Format::Float);
+    assert_eq!(Format::detect("lc5", &float), Format::Float);
+    // 不足一根 K 线时，使用扩展名
+    assert_eq!(Format::detect("5", &[]), Format::Integer);
+    assert_eq!(Format::detect("lc5", &[]), Format::Float);
+    // 价格无法区分时，使用扩展名：全为 0，或者两种方式的 K 线混在一起
+    assert_eq!(Format::detect("5", &[0; 64]), Format::Integer);
+    assert_eq!(Format::detect("lc5", &[0; 64]), Format::Float);
+    let mixed = [&bytes[..32], &float[32..64]].concat();
+    assert_eq!(Format::detect("5", &mixed), Format::Integer);
+    assert_eq!(Format::detect("lc5", &mixed), Format::Float);
+
+    let file = LcFile::from_path("assets/sz000001.5").unwrap();
+    assert_eq!(
+        (file.symbol(), file.period),
+        ("sz000001".into(), Period::Min5)
+    );
+    let rows = file.rows()?.collect::<rustdx::Result<Vec<_>>>()?;
+    assert_eq!(rows.len(), 6);
+    assert_eq!(rows[0].datetime, "2021-08-10 09:35");
+    assert_eq!(rows[5].datetime, "2021-08-10 10:00");
+    assert_eq!(
+        (rows[0].open, rows[0].high, rows[0].low, rows[0].close),
+        (18.5, 18.62, 18.48, 18.6)
+    );
+    assert_eq!((rows[0].amount, rows[0].vol), (4.1e7, 2212300));
+    // 以 f32 解析时，价格是非常小的非正规数
+    let wrong = Lc::parse(1, &bytes, Format::Float);
+    assert!(wrong[0].open < 1e-30);
+    Ok(())
+}
+
+#[test]
+fn lc_to_bytes() -> rustdx::Result<()> {
+    let legacy = fs::read("assets/sz000001.5")?;
+    let lcs = Lc::parse(1, &legacy, Format::Integer);
+    let round_trip: Vec<u8> = lcs.iter().flat_map(Lc::to_int_bytes).collect();
+    assert_eq!(round_trip, legacy);
+
+    let float = [lc_bytes(571, 10.1), lc_bytes(572, 10.2)].concat();
+    let lcs = Lc::parse(1, &float, Format::Float);
+    let round_trip: Vec<u8> = lcs.iter().flat_map(Lc::to_bytes).collect();
+    assert_eq!(round_trip, float);
+
+    // 旧格式转换成 lc5 文件
+    let out = std::env::temp_dir().join(format!("rustdx-lc-{}", std::process::id()));
+    fs::create_dir_all(&out)?;
+    let path = out.join("sz000001.lc5");
+    let lcs = Lc::parse(1, &legacy, Format::Integer);
+    Lc::append_to_file(&lcs[..3], &path, Format::Float)?;
+    Lc::append_to_file(&lcs[3..], &path, Format::Float)?;
+    let bytes = fs::read(&path)?;
+    assert_eq!(Format::detect("lc5", &bytes), Format::Float);
+    let converted = LcFile::from_path(&path).unwrap().read()?;
+    assert_eq!(format!("{converted:?}"), format!("{lcs:?}"));
+    fs::remove_dir_all(out)?;
+    Ok(())
+}
+
+/// 超过 `Format::detect` 所检查的 K 线数量时，逐根读取的结果与一次性读取相同。
+#[test]
+fn lc_rows_stream() -> rustdx::Result<()> {
+    let dir = std::env::temp_dir().join(format!("rustdx-lc-rows-{}", std::process::id()));
+    fs::create_dir_all(&dir)?;
+    let path = dir.join("sz000001.lc1");
+    let mut bytes: Vec<u8> = (0..20)
+        .flat_map(|i| lc_bytes(571 + i, 10. + i as f32 / 100.))
+        .collect();
+    // 末尾不足 32 字节的部分被忽略
+    bytes.extend_from_slice(&[0; 5]);
+    fs::write(&path, bytes)?;
+
+    let file = LcFile::from_path(&path).unwrap();
+    let rows = file.rows()?.collect::<rustdx::Result<Vec<_>>>()?;
+    let read: Vec<_> = file
+        .read()?
+        .into_iter()
+        .map(|lc| lc.into_serde_with_exchange("sz"))
+        .collect();
+    assert_eq!(rows.len(), 20);
+    assert_eq!(format!("{rows:?}"), format!("{read:?}"));
+    assert_eq!(rows[19].datetime, "2021-08-10 09:50");
+    // 不经过 LcFile 时，没有交易所
+    assert_eq!(file.read()?.remove(0).into_serde_type().exchange, "");
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
@@ -1,7 +1,7 @@
 // use insta::{assert_debug_snapshot, assert_yaml_snapshot};
 use insta::assert_debug_snapshot;
 use rustdx::tcp::{self, Tcp, Tdx};
-use std::io::Result;
+use rustdx::Result;
 
 #[test]
 fn tcp_security_count() -> Result<()> {